@@ -0,0 +1,61 @@
+//! Startup time of the `resolc` wrapper: time it takes to find the binary to run.
+//!
+//! Run with `cargo bench --bench startup`.
+
+use std::time::{Duration, Instant};
+
+use rvm::{VersionManager, HOME_ENV};
+
+/// Number of installed versions, nightlies make large stores common
+const VERSIONS: usize = 200;
+const ITERATIONS: u32 = 200;
+
+fn measure(name: &str, mut f: impl FnMut()) -> Duration {
+    f();
+    let start = Instant::now();
+    for _ in 0..ITERATIONS {
+        f();
+    }
+    let elapsed = start.elapsed() / ITERATIONS;
+    println!("{name:<24}{elapsed:>12.2?} per resolution");
+    elapsed
+}
+
+fn main() {
+    let store = tempfile::tempdir().expect("temporary store");
+    for patch in 0..VERSIONS {
+        let version = format!("0.3.{patch}");
+        let dir = store.path().join(&version);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("resolc"), &version).unwrap();
+        let build = serde_json::json!({
+            "name": "resolc",
+            "version": version,
+            "longVersion": format!("{version}+commit.0000000"),
+            "url": "https://example.com/resolc",
+            "sha256": "00",
+            "firstSolcVersion": "0.8.0",
+            "lastSolcVersion": "0.8.30"
+        });
+        std::fs::write(dir.join("build.json"), build.to_string()).unwrap();
+    }
+    std::fs::write(store.path().join(".default_version"), "0.3.0").unwrap();
+    std::env::set_var(HOME_ENV, store.path());
+
+    let project = tempfile::tempdir().expect("temporary project");
+    let dir = project.path();
+    // Creates the store index.
+    VersionManager::new(true).unwrap();
+
+    let full = measure("full resolver", || {
+        let manager = VersionManager::new(true).unwrap();
+        manager.resolve(None, dir).unwrap();
+    });
+    let indexed = measure("store index", || {
+        VersionManager::resolve_indexed(None, dir).expect("version is in the index");
+    });
+    println!(
+        "store index is {:.1}x faster with {VERSIONS} installed versions",
+        full.as_secs_f64() / indexed.as_secs_f64()
+    );
+}
@@ -1,8 +1,11 @@
 //! resolc wrapper
 
-use anyhow::Context;
-use rvm::VersionManager;
+use rvm::{
+    shim_selector, Binary, Candidate, Config, Error, Source, VersionManager, RESOLVE_SOURCES_ENV,
+};
+use std::ffi::OsString;
 use std::io;
+use std::path::{Path, PathBuf};
 use std::process::{Command, ExitStatus, Stdio};
 
 fn main() {
@@ -17,25 +20,96 @@ fn main() {
 }
 
 fn runner() -> anyhow::Result<i32> {
-    let mut args = std::env::args_os().skip(1).peekable();
+    let mut args = std::env::args_os().peekable();
+    // Versioned shims like `resolc-0.4.0` select the version by the name they are started with.
+    let program = args.next().map(PathBuf::from).unwrap_or_default();
+    let selector = match shim_selector(&program) {
+        Some(selector) => Some(selector),
+        None => {
+            let selector = args
+                .peek()
+                .and_then(|str| str.to_str())
+                .and_then(|arg| arg.strip_prefix('+'))
+                .map(ToOwned::to_owned);
+            if selector.is_some() {
+                args.next();
+            }
+            selector
+        }
+    };
+    let dir = std::env::current_dir()?;
+    let args: Vec<OsString> = args.collect();
+    let selector = match selector {
+        Some(selector) => Some(selector),
+        None => sources_selector(&args, &dir)?,
+    };
+
+    // Build tools run the wrapper many times, so the store index is tried before loading every installed build.
+    let bin_path = match VersionManager::resolve_indexed(selector.as_deref(), &dir) {
+        Some(path) => path,
+        None => resolve(selector.as_deref(), &dir)?,
+    };
+
+    VersionManager::record_use(&bin_path);
+
+    let mut cmd = Command::new(bin_path);
+    cmd.args(args)
+        .stdin(Stdio::inherit())
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::inherit());
+    Ok(exec(&mut cmd)?.code().unwrap_or(-1))
+}
+
+/// Picks the version from the `pragma solidity` constraints of the compiled `.sol` files when enabled,
+/// only if nothing but the global default selects a version
+///
+/// Runs on every compilation, so the configuration is only read for `.sol` arguments without the environment
+/// variable, and only installed builds are considered. Release lists are fetched only to install a missing version.
+fn sources_selector(args: &[OsString], dir: &Path) -> anyhow::Result<Option<String>> {
+    let sources: Vec<PathBuf> = args
+        .iter()
+        .map(PathBuf::from)
+        .filter(|path| path.extension().is_some_and(|ext| ext == "sol") && path.is_file())
+        .collect();
+    if sources.is_empty() {
+        return Ok(None);
+    }
+    // A broken configuration is reported by the resolver, here it only disables the feature.
+    let config = Config::load().unwrap_or_default();
+    let enabled = match std::env::var(RESOLVE_SOURCES_ENV) {
+        Ok(value) if !value.is_empty() => matches!(value.as_str(), "1" | "true"),
+        _ => config.resolve_sources,
+    };
+    if !enabled {
+        return Ok(None);
+    }
+
     let manager = VersionManager::new(true)?;
-    let bin = {
-        if let Some(version) = args
-            .peek()
-            .and_then(|str| str.to_str())
-            .and_then(|arg| arg.strip_prefix('+'))
-            .map(|arg| {
-                arg.parse::<semver::Version>()
-                    .context("failed to parse version specifier")
-            })
-            .transpose()
-            .context("failed to parse version specifier")?
-        {
-            args.next();
-            manager.get(&version, None)?
-        } else {
-            manager.get_default()?
+    let selected = manager.candidates(None, dir)?.into_iter().next();
+    if selected.is_some_and(|candidate| !matches!(candidate.source, Source::Default { .. })) {
+        return Ok(None);
+    }
+    let resolution = match manager.resolve_sources(&sources, None, false) {
+        Ok(resolution) => resolution,
+        Err(Error::NoCompatibleCompiler) if config.auto_install() => {
+            eprintln!("rvm: no installed Resolc version compiles the sources, installing one");
+            VersionManager::new(false)?.resolve_sources(&sources, None, true)?
         }
+        Err(err) => return Err(err.into()),
+    };
+    Ok(Some(resolution.resolc.to_string()))
+}
+
+/// Resolves the binary with the full resolver, installing it if auto-install is enabled
+fn resolve(selector: Option<&str>, dir: &Path) -> anyhow::Result<PathBuf> {
+    let manager = VersionManager::new(true)?;
+    // `+<name>` selects a binary registered with `rvm link`
+    let bin = match manager.resolve(selector, dir) {
+        Ok(resolution) => resolution.binary,
+        Err(Error::SelectionFailed {
+            selector, reason, ..
+        }) if is_missing(&reason) && manager.config()?.auto_install() => install(&selector, dir)?,
+        Err(err) => return Err(err.into()),
     };
 
     let bin_path = bin.local().expect("should not fail");
@@ -47,13 +121,31 @@ fn runner() -> anyhow::Result<i32> {
             bin_path.display()
         );
     }
+    Ok(bin_path.to_path_buf())
+}
 
-    let mut cmd = Command::new(bin_path);
-    cmd.args(args)
-        .stdin(Stdio::inherit())
-        .stdout(Stdio::inherit())
-        .stderr(Stdio::inherit());
-    Ok(exec(&mut cmd)?.code().unwrap_or(-1))
+/// Whether the selected version can be installed
+fn is_missing(err: &Error) -> bool {
+    matches!(
+        err,
+        Error::NotInstalled { .. } | Error::UnknownVersion { .. } | Error::NoMatchingVersion { .. }
+    )
+}
+
+/// Installs the newest release matching the selector, or the locked binary if the selector comes from `rvm.lock`.
+/// Progress is reported on stderr to keep stdout for the compiler
+fn install(selector: &str, dir: &Path) -> anyhow::Result<Binary> {
+    eprintln!("rvm: Resolc {selector} is not installed, installing it");
+    let manager = VersionManager::new(false)?;
+    let binary = match VersionManager::project_version(dir)? {
+        Some(Candidate {
+            source: Source::LockFile { .. },
+            selector: locked,
+        }) if locked == selector => manager.sync(dir)?,
+        _ => manager.get_or_install_matching(selector)?,
+    };
+    eprintln!("rvm: Resolc v{} is installed succesfully", binary.version());
+    Ok(binary)
 }
 
 fn exec(cmd: &mut Command) -> io::Result<ExitStatus> {
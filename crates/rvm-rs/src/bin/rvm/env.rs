@@ -0,0 +1,92 @@
+//! Shell integration: `rvm env` and `rvm completions`
+
+use std::path::Path;
+
+use clap::{CommandFactory, ValueEnum};
+use rvm::VERSION_ENV;
+
+use crate::Cli;
+
+/// Shells supported by `rvm env`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum Shell {
+    /// Bash, hooked into `PROMPT_COMMAND`
+    Bash,
+    /// Zsh, hooked into `chpwd`
+    Zsh,
+    /// Fish, hooked on `PWD` changes
+    Fish,
+}
+
+/// Shell code putting the shim folder on `PATH` and exporting the project version on directory changes.
+///
+/// The hook only manages `RESOLC_VERSION` it exported itself, so versions set by the user are left alone.
+pub fn env(shell: Shell, shim_dir: &Path) -> String {
+    let shim_dir = shim_dir.display();
+    let use_project = match shell {
+        Shell::Bash | Shell::Zsh => format!(
+            r#"__rvm_use_project() {{
+  if [ -n "${VERSION_ENV}" ] && [ "${VERSION_ENV}" != "$__RVM_PROJECT_VERSION" ]; then
+    return
+  fi
+  local version
+  version="$(command rvm env --project-version 2>/dev/null)"
+  if [ -n "$version" ]; then
+    export {VERSION_ENV}="$version"
+    export __RVM_PROJECT_VERSION="$version"
+  elif [ -n "$__RVM_PROJECT_VERSION" ]; then
+    unset {VERSION_ENV} __RVM_PROJECT_VERSION
+  fi
+}}
+"#
+        ),
+        Shell::Fish => format!(
+            r#"function __rvm_use_project --on-variable PWD
+  if set -q {VERSION_ENV}; and test "${VERSION_ENV}" != "$__RVM_PROJECT_VERSION"
+    return
+  end
+  set -l version (command rvm env --project-version 2>/dev/null)
+  if test -n "$version"
+    set -gx {VERSION_ENV} $version
+    set -gx __RVM_PROJECT_VERSION $version
+  else if set -q __RVM_PROJECT_VERSION
+    set -e {VERSION_ENV}
+    set -e __RVM_PROJECT_VERSION
+  end
+end
+"#
+        ),
+    };
+    let hook = match shell {
+        Shell::Bash => r#"__rvm_cd_hook() {
+  if [ "$__RVM_LAST_PWD" != "$PWD" ]; then
+    __RVM_LAST_PWD="$PWD"
+    __rvm_use_project
+  fi
+}
+case ";${PROMPT_COMMAND:-};" in
+  *";__rvm_cd_hook;"*) ;;
+  *) PROMPT_COMMAND="__rvm_cd_hook${PROMPT_COMMAND:+;$PROMPT_COMMAND}" ;;
+esac
+"#
+        .to_owned(),
+        Shell::Zsh => r#"autoload -U add-zsh-hook
+add-zsh-hook chpwd __rvm_use_project
+__rvm_use_project
+"#
+        .to_owned(),
+        Shell::Fish => "__rvm_use_project\n".to_owned(),
+    };
+    let path = match shell {
+        Shell::Bash | Shell::Zsh => format!("export PATH=\"{shim_dir}:$PATH\"\n"),
+        Shell::Fish => format!("fish_add_path --global --prepend \"{shim_dir}\"\n"),
+    };
+    format!("{path}{use_project}{hook}")
+}
+
+/// Completion script for the shell generated from the `rvm` command definitions
+pub fn completions(shell: clap_complete::Shell) -> String {
+    let mut script = Vec::new();
+    clap_complete::generate(shell, &mut Cli::command(), "rvm", &mut script);
+    String::from_utf8_lossy(&script).into_owned()
+}
@@ -0,0 +1,157 @@
+//! `rvm list` table view
+
+use std::{cmp::Reverse, time::SystemTime};
+
+use clap::{Parser, ValueEnum};
+use rvm::{Binary, Channel, Source, VersionManager};
+use semver::Version;
+use serde_json::json;
+
+use crate::output::{date, size, table, Format, Output};
+
+/// Order of the versions printed by `rvm list`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum Sort {
+    /// Oldest version first
+    #[default]
+    Version,
+    /// Most recently installed first, versions that aren't installed last
+    Installed,
+    /// Most recently used first, versions that were never used last
+    LastUsed,
+}
+
+/// Filters of `rvm list`.
+#[derive(Debug, Parser, Clone)]
+pub struct ListArgs {
+    /// Only show installed versions
+    #[arg(long, default_value_t = false, conflicts_with = "remote")]
+    installed: bool,
+    /// Only show versions that are available to install
+    #[arg(long, default_value_t = false)]
+    remote: bool,
+    /// Only show versions of the channel
+    #[arg(long)]
+    channel: Option<Channel>,
+    /// Only show versions supporting the given `solc` version
+    #[arg(long, value_name = "SOLC_VERSION")]
+    for_solc: Option<Version>,
+    /// Only show versions starting from the given one
+    #[arg(long, value_name = "VERSION")]
+    since: Option<Version>,
+    /// Only show the given number of the newest versions
+    #[arg(long, value_name = "N")]
+    limit: Option<usize>,
+    /// Order of the versions, `--limit` still keeps the newest versions
+    #[arg(long, value_enum, default_value_t = Sort::Version)]
+    sort: Sort,
+}
+
+/// Prints available and installed versions matching the filters
+pub fn list(manager: &VersionManager, args: ListArgs, output: Output) -> anyhow::Result<()> {
+    let mut binaries: Vec<Binary> = manager
+        .list_available(args.for_solc)?
+        .into_iter()
+        .filter(|binary| match binary {
+            Binary::Local { .. } => !args.remote,
+            Binary::Remote(_) => !args.installed,
+        })
+        .filter(|binary| {
+            args.channel
+                .is_none_or(|channel| Channel::of(binary.version()) == channel)
+        })
+        .filter(|binary| {
+            args.since
+                .as_ref()
+                .is_none_or(|since| binary.version() >= since)
+        })
+        .collect();
+    if let Some(limit) = args.limit {
+        binaries.drain(..binaries.len().saturating_sub(limit));
+    }
+    let time = |binary: &Binary| -> Option<SystemTime> {
+        match args.sort {
+            Sort::Version => None,
+            Sort::Installed => manager.installed_at(binary),
+            Sort::LastUsed => manager.last_used(binary),
+        }
+    };
+    // The sort is stable, so versions with the same time keep the version order.
+    binaries.sort_by_key(|binary| Reverse(time(binary)));
+    let default = manager
+        .get_default()
+        .ok()
+        .map(|default| default.version().clone());
+    let project = manager
+        .candidates(None, &std::env::current_dir()?)?
+        .into_iter()
+        .find(|candidate| {
+            matches!(
+                candidate.source,
+                Source::ProjectFile { .. } | Source::LockFile { .. } | Source::ToolConfig { .. }
+            ) && !candidate.is_path()
+        })
+        .map(|candidate| candidate.selector);
+
+    if output.format == Format::Json {
+        return output.json(&json!({
+            "default": default,
+            "project": project,
+            "binaries": binaries,
+        }));
+    }
+
+    if binaries.is_empty() {
+        output.text("No Resolc versions found");
+        return Ok(());
+    }
+    let mut rows = vec![[
+        String::new(),
+        "VERSION".to_owned(),
+        "CHANNEL".to_owned(),
+        "SOLC".to_owned(),
+        "STATUS".to_owned(),
+        "SIZE".to_owned(),
+        "INSTALLED".to_owned(),
+        "LAST USED".to_owned(),
+    ]];
+    for binary in &binaries {
+        let info = binary.info();
+        let is_default = matches!(binary, Binary::Local { info, .. } if info.link.is_none())
+            && default.as_ref() == Some(&info.version);
+        let is_project = project.as_deref().is_some_and(|project| match &info.link {
+            Some(link) => link == project,
+            None => info.version.to_string() == project,
+        });
+        let status = match (binary, &info.link) {
+            (Binary::Local { .. }, Some(link)) => format!("linked as {link}"),
+            (Binary::Local { .. }, None) => "installed".to_owned(),
+            (Binary::Remote(_), _) => "available".to_owned(),
+        };
+        let size = binary
+            .local()
+            .and_then(|path| std::fs::metadata(path).ok())
+            .map(|metadata| size(metadata.len()))
+            .unwrap_or_default();
+        rows.push([
+            format!(
+                "{}{}",
+                if is_default { "*" } else { "" },
+                if is_project { ">" } else { "" }
+            ),
+            info.version.to_string(),
+            Channel::of(&info.version).to_string(),
+            format!(
+                "{} - {}",
+                info.first_supported_solc_version, info.last_supported_solc_version
+            ),
+            status,
+            size,
+            manager.installed_at(binary).map(date).unwrap_or_default(),
+            manager.last_used(binary).map(date).unwrap_or_default(),
+        ]);
+    }
+
+    output.text(table(&rows));
+    Ok(())
+}
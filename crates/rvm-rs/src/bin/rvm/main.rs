@@ -1,11 +1,23 @@
 //! Main Resolc version manager entrypoint  
 
-use std::time::Duration;
+mod env;
+mod list;
+mod output;
+mod run;
+mod serve;
+mod upgrade;
 
-use clap::{Parser, Subcommand};
-use indicatif::ProgressBar;
-use rvm::{Binary, Error, VersionManager};
+use std::{path::PathBuf, time::Duration};
+
+use clap::{CommandFactory, FromArgMatches, Parser, Subcommand};
+use output::{Format, MessageFormat, Output};
+use rvm::{
+    Bundle, Channel, Config, Error, Mirror, MirrorOptions, Platform, PrunePolicy, Registry,
+    RegistryVersion, VersionManager, VersionSpec, LOCK_FILE_NAME, OFFICIAL_REGISTRY,
+};
 use semver::Version;
+use serde_json::json;
+use url::Url;
 
 const VERSION: &str = env!("CARGO_PKG_VERSION");
 
@@ -18,6 +30,12 @@ struct Cli {
     /// Run in offline mode
     #[arg(short, long, default_value_t = false)]
     offline: bool,
+    /// Format of command results printed to stdout
+    #[arg(long, global = true, value_enum, default_value_t)]
+    format: Format,
+    /// Format of progress messages printed to stderr
+    #[arg(long, global = true, value_enum, default_value_t)]
+    message_format: MessageFormat,
     #[clap(subcommand)]
     command: Rvm,
 }
@@ -27,26 +45,263 @@ struct Cli {
 enum Rvm {
     /// Install given version of Resolc
     Install {
-        /// Resolc version
-        version: Version,
+        /// Resolc version, optionally prefixed with the registry to install it from, e.g. `internal:0.4.0`
+        version: RegistryVersion,
         /// Use as default Resolc version,
         #[arg(long, default_value_t = false)]
         set_default: bool,
     },
     /// Uninstall given version of Resolc
     Remove(WithVersion),
+    /// Remove installed versions selected by retention policies, a version is removed when any policy selects it.
+    /// The default version, the version pinned by the project and linked binaries are kept
+    Prune {
+        /// Keep only the given number of the newest nightlies
+        #[arg(long, value_name = "N")]
+        keep_nightlies: Option<usize>,
+        /// Remove nightlies of versions that have a stable release
+        #[arg(long, default_value_t = false)]
+        superseded: bool,
+        /// Remove versions that weren't used for the given number of days
+        #[arg(long, value_name = "DAYS")]
+        unused_for: Option<u64>,
+        /// Remove versions matching the requirement, e.g. `<0.3`
+        #[arg(long, value_name = "REQ")]
+        matching: Option<semver::VersionReq>,
+        /// Only print the versions that would be removed
+        #[arg(long, default_value_t = false)]
+        dry_run: bool,
+    },
     /// Print path to the installed Resolc version
     Which(WithVersion),
+    /// Show the installed, default and project versions with the newest releases of their channels
+    Outdated,
+    /// Install the newest Resolc release and make it the default, or pin it in the project file with `--project`
+    Upgrade {
+        /// Channel to upgrade to, the channel of the current version by default
+        #[arg(long)]
+        channel: Option<Channel>,
+        /// Rewrite the closest `.resolc-version` project file instead of switching the default
+        #[arg(long, default_value_t = false)]
+        project: bool,
+        /// Remove the previous version, unless it's still the default or pinned by the project
+        #[arg(long, default_value_t = false)]
+        prune: bool,
+    },
+    /// Print which Resolc binary `resolc` runs in the current directory and why
+    Current {
+        /// Resolve as if `resolc +<selector>` was run
+        selector: Option<String>,
+    },
+    /// Pick the newest Resolc and `solc` pair that compiles the sources, by their `pragma solidity` constraints
+    Resolve {
+        /// `.sol` files and directories to search recursively
+        #[arg(long, value_name = "PATH", required = true, num_args = 1..)]
+        sources: Vec<PathBuf>,
+        /// Channel of the Resolc releases, stable by default
+        #[arg(long)]
+        channel: Option<Channel>,
+        /// Install the Resolc version if it's missing
+        #[arg(long, default_value_t = false)]
+        install: bool,
+    },
+    /// Run a command with the given Resolc version first on `PATH` as `resolc`, e.g. `rvm run 0.4.0 -- forge build`.
+    /// Missing versions are installed
+    Run {
+        /// Resolc version, version specification (e.g. `latest-nightly`) or name of a linked binary
+        version: String,
+        /// Install into a temporary store that is removed once the command exits
+        #[arg(long, default_value_t = false)]
+        ephemeral: bool,
+        /// Command to run and its arguments
+        #[arg(last = true, required = true)]
+        command: Vec<std::ffi::OsString>,
+    },
+    /// Print shell code that puts the shims on `PATH` and exports the version of the project on directory changes,
+    /// e.g. `eval "$(rvm env --shell bash)"` in `~/.bashrc`
+    Env {
+        /// Shell to print the code for
+        #[arg(long, value_enum, required_unless_present = "project_version")]
+        shell: Option<env::Shell>,
+        /// Print the version pinned by the project in the current directory instead, used by the shell hook
+        #[arg(long, default_value_t = false, hide = true)]
+        project_version: bool,
+    },
+    /// Print the completion script for the shell
+    Completions {
+        /// Shell to print the script for
+        shell: clap_complete::Shell,
+    },
+    /// Regenerate the `resolc` and `resolc-<version>` shims and print the folder they are in.
+    /// Add the folder to `PATH` to run fixed versions as `resolc-<version>`
+    Shims,
+    /// Print full metadata of the given Resolc version
+    Info(WithVersion),
     /// Set a default Resolc version to use
     Use {
-        /// Resolc version
-        version: Version,
+        /// Resolc version, version specification (e.g. `latest`) or alias
+        #[arg(required_unless_present = "previous")]
+        version: Option<String>,
         /// Install Resolc binary if it's not already installed
         #[arg(long, default_value_t = false)]
         install: bool,
+        /// Switch back to the previous default version
+        #[arg(long, default_value_t = false, conflicts_with_all = ["version", "install"])]
+        previous: bool,
+    },
+    /// List past default versions, the most recent first
+    History,
+    /// Pin the exact build of a version for the project in `rvm.lock`, with the sha256 and URL of every platform
+    Lock {
+        /// Version, version specification or alias, the version `resolc` runs in the current directory by default
+        version: Option<String>,
+        /// Platforms to lock, every platform the version is released for by default
+        #[arg(long = "platform", value_name = "PLATFORM")]
+        platforms: Vec<Platform>,
     },
-    /// List all available and installed versions of Resolc.
-    /// Also prints default Resolc version if it's present.
+    /// Install exactly the binary locked by the closest `rvm.lock`, fails on any sha256 difference
+    Sync {
+        /// Install the versions requested by every profile of `foundry.toml` or a hardhat configuration instead
+        #[arg(long, value_name = "PATH")]
+        from: Option<PathBuf>,
+    },
+    /// Manage aliases of versions, e.g. `rvm alias set prod 0.3.0`, usable wherever a version is accepted
+    #[command(subcommand)]
+    Alias(AliasCommand),
+    /// List available and installed versions of Resolc, the default version is marked with `*`, the project one with `>`
+    List(list::ListArgs),
+    /// Register a locally built Resolc binary under a name, so it can be used as `resolc +<name>`
+    Link {
+        /// Name to register the binary under
+        name: String,
+        /// Path to the binary
+        path: PathBuf,
+    },
+    /// Remove a linked binary, the binary itself is not deleted
+    Unlink {
+        /// Name of the linked binary
+        name: String,
+    },
+    /// Import Resolc binaries that are already present on the system.
+    /// Binaries matching a known release by sha256 are installed, others are reported as unknown builds
+    Import {
+        /// Also search directories listed in `PATH`, without their subdirectories
+        #[arg(long, default_value_t = false)]
+        scan: bool,
+        /// Binaries and directories to search, directories are searched recursively
+        paths: Vec<PathBuf>,
+    },
+    /// Publish an installed version of Resolc to an OCI registry
+    Push {
+        /// Resolc version
+        version: Version,
+        /// Name of the OCI registry
+        registry: String,
+    },
+    /// Manage registries Resolc versions are installed from
+    #[clap(subcommand)]
+    Registry(RegistryCommand),
+    /// Manage local mirrors of the official releases
+    #[clap(subcommand)]
+    Mirror(MirrorCommand),
+    /// Export and import installed versions for machines without network access
+    #[clap(subcommand)]
+    Bundle(BundleCommand),
+    /// Serve installed versions (or a mirror) as a release repository over HTTP.
+    /// Other machines can use it with `rvm registry add <name> http://<address>/`
+    Serve {
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1:8080")]
+        address: String,
+        /// Serve a mirror directory created by `rvm mirror sync` instead of installed versions
+        #[arg(long)]
+        mirror: Option<PathBuf>,
+        /// URL clients use to reach the server, taken from the `Host` header by default
+        #[arg(long)]
+        public_url: Option<Url>,
+    },
+}
+
+/// Offline bundles.
+#[derive(Debug, Subcommand)]
+enum BundleCommand {
+    /// Pack installed versions into a bundle
+    Create {
+        /// Installed Resolc versions to pack
+        #[arg(long, required = true, value_delimiter = ',', num_args = 1..)]
+        versions: Vec<Version>,
+        /// Path of the bundle
+        #[arg(short, long)]
+        output: PathBuf,
+    },
+    /// Verify and install every version of a bundle
+    Import {
+        /// Path of the bundle
+        bundle: PathBuf,
+        /// Use the default version of the bundle as default Resolc version
+        #[arg(long, default_value_t = false)]
+        set_default: bool,
+    },
+}
+
+/// Mirror management.
+#[derive(Debug, Subcommand)]
+enum MirrorCommand {
+    /// Download missing builds into the mirror directory and verify the present ones
+    Sync {
+        /// Mirror directory
+        dir: PathBuf,
+        /// Versions to mirror: exact versions, requirements or `latest`/`latest-nightly`, all by default
+        #[arg(long = "version", value_name = "SPEC")]
+        versions: Vec<VersionSpec>,
+        /// Channels to mirror, all by default
+        #[arg(long = "channel", value_name = "CHANNEL")]
+        channels: Vec<Channel>,
+        /// Platforms to mirror, the current platform by default
+        #[arg(long = "platform", value_name = "PLATFORM")]
+        platforms: Vec<Platform>,
+    },
+}
+
+/// Alias management.
+#[derive(Debug, Subcommand)]
+enum AliasCommand {
+    /// Point an alias at a version or version specification, replaces the previous target
+    Set {
+        /// Alias name, e.g. `prod`
+        name: String,
+        /// Resolc version or version specification, e.g. `0.3.0` or `latest-nightly`
+        target: String,
+    },
+    /// Remove an alias
+    Remove {
+        /// Alias name
+        name: String,
+    },
+    /// List aliases with the installed versions they select
+    List,
+}
+
+/// Registry management.
+#[derive(Debug, Subcommand)]
+enum RegistryCommand {
+    /// Add a named registry, replaces the registry with the same name
+    Add {
+        /// Registry name
+        name: String,
+        /// URL of the `list.json` release index
+        url: Url,
+        /// Registries with higher priority win when several of them provide the same version,
+        /// the official registry has priority 0
+        #[arg(long, default_value_t = 0, allow_negative_numbers = true)]
+        priority: i32,
+    },
+    /// Remove a named registry
+    Remove {
+        /// Registry name
+        name: String,
+    },
+    /// List all registries in priority order
     List,
 }
 #[allow(missing_docs)]
@@ -56,17 +311,15 @@ pub struct WithVersion {
     version: Version,
 }
 
-fn spinner(msg: String) -> ProgressBar {
-    let spinner = ProgressBar::new_spinner();
-    spinner.enable_steady_tick(Duration::from_millis(150));
-    spinner.set_message(msg);
-    spinner
-}
-
-fn exec(is_offline: bool, rvm: Rvm, manager: VersionManager) -> anyhow::Result<(), anyhow::Error> {
+fn exec(
+    is_offline: bool,
+    output: Output,
+    rvm: Rvm,
+    manager: VersionManager,
+) -> anyhow::Result<(), anyhow::Error> {
     match rvm {
         Rvm::Install {
-            version,
+            version: RegistryVersion { registry, version },
             set_default,
         } => {
             if is_offline {
@@ -74,71 +327,706 @@ fn exec(is_offline: bool, rvm: Rvm, manager: VersionManager) -> anyhow::Result<(
             }
 
             if manager.is_installed(&version) {
-                println!("Resolc v{} is already installed", version);
+                // Fails when the installed build comes from another registry than the requested one.
+                let binary = manager.get_or_install_from(registry.as_deref(), &version, None)?;
+                output.text(format!("Resolc v{} is already installed", version));
+                output.json(&json!({
+                    "binary": binary,
+                    "installed": false,
+                    "default": false,
+                }))?;
                 return Ok(());
             }
 
-            let spinner = spinner(format!("Downloading and installing Resolc v{}", version));
-            manager.get_or_install(&version, None)?;
-            spinner.finish_with_message(format!("Resolc v{} is installed succesfully", version));
+            let progress = output.progress(
+                "install",
+                version.to_string(),
+                format!("Downloading and installing Resolc v{}", version),
+            );
+            let binary = manager.get_or_install_from(registry.as_deref(), &version, None)?;
+            progress.finish(format!("Resolc v{} is installed succesfully", version));
             if set_default {
                 manager.set_default(&version)?;
-                println!("Succesfully set Resolc v{} as default", version)
+                output.text(format!("Succesfully set Resolc v{} as default", version));
             }
+            output.json(&json!({
+                "binary": binary,
+                "installed": true,
+                "default": set_default,
+            }))?;
         }
         Rvm::Remove(WithVersion { version }) => {
             manager.remove(&version)?;
-            println!("Resolc v{} is removed succesfully", version);
+            output.text(format!("Resolc v{} is removed succesfully", version));
+            output.json(&json!({ "removed": version }))?;
         }
-        Rvm::List => {
-            let versions = manager.list_available(None)?;
-            if let Ok(default) = manager.get_default() {
-                println!("Default version of Resolc is: {}", default.version())
+        Rvm::Prune {
+            keep_nightlies,
+            superseded,
+            unused_for,
+            matching,
+            dry_run,
+        } => {
+            let policy = PrunePolicy {
+                keep_nightlies,
+                superseded,
+                unused_for: unused_for.map(|days| Duration::from_secs(days * 24 * 60 * 60)),
+                matching,
+            };
+            if policy.is_empty() {
+                anyhow::bail!("No retention policy is given, see `rvm prune --help`");
             }
-            println!(
-                "Available to install Resolc version: {:?}",
-                versions
-                    .iter()
-                    .filter_map(|x| match x {
-                        Binary::Remote(binary_info) => Some(binary_info.version.to_string()),
-                        _ => None,
-                    })
-                    .collect::<Vec<String>>()
-            );
-            println!(
-                "Already installed Resolc versions: {:?}",
-                versions
-                    .iter()
-                    .filter_map(|x| match x {
-                        Binary::Local { info, .. } => Some(info.version.to_string()),
-                        _ => None,
-                    })
-                    .collect::<Vec<String>>()
-            )
+            let pruned = manager.prune(&policy, &std::env::current_dir()?, dry_run)?;
+            if pruned.is_empty() {
+                output.text("Nothing to prune");
+            }
+            for version in &pruned {
+                if dry_run {
+                    output.text(format!("Resolc v{} would be removed", version));
+                } else {
+                    output.text(format!("Resolc v{} is removed succesfully", version));
+                }
+            }
+            output.json(&json!({ "removed": pruned, "dryRun": dry_run }))?;
+        }
+        Rvm::List(args) => list::list(&manager, args, output)?,
+        Rvm::Use { previous: true, .. } => {
+            let binary = manager.use_previous()?;
+            output.text(format!(
+                "Succesfully switched back to Resolc v{} as default",
+                binary.version()
+            ));
+            output.json(&json!({ "default": binary }))?;
         }
-        Rvm::Use { version, install } => {
+        Rvm::Use {
+            version, install, ..
+        } => {
+            let selector = version.expect("version is required without --previous");
+            let version = match selector.parse::<Version>() {
+                Ok(version) => version,
+                Err(_) => match manager.select(&selector) {
+                    Ok(binary) if binary.info().link.is_none() => binary.version().clone(),
+                    Ok(_) => anyhow::bail!(
+                        "Linked binaries can't be the default, run them with `resolc +{selector}`"
+                    ),
+                    Err(Error::NoMatchingVersion { .. }) if !is_offline && install => {
+                        let progress = output.progress(
+                            "install",
+                            selector.clone(),
+                            format!("Downloading and installing Resolc {}", selector),
+                        );
+                        let binary = manager.get_or_install_matching(&selector)?;
+                        progress.finish(format!(
+                            "Resolc v{} is installed succesfully",
+                            binary.version()
+                        ));
+                        binary.version().clone()
+                    }
+                    Err(err) => return Err(err.into()),
+                },
+            };
             if !is_offline && install && manager.get(&version, None).is_err() {
-                let spinner = spinner(format!("Downloading and installing Resolc v{}", version));
+                let progress = output.progress(
+                    "install",
+                    version.to_string(),
+                    format!("Downloading and installing Resolc v{}", version),
+                );
                 manager.get_or_install(&version, None)?;
-                spinner
-                    .finish_with_message(format!("Resolc v{} is installed succesfully", version));
+                progress.finish(format!("Resolc v{} is installed succesfully", version));
             }
             manager.set_default(&version)?;
-            println!("Succesfully set Resolc v{} as default", version)
+            output.text(format!("Succesfully set Resolc v{} as default", version));
+            output.json(&json!({ "default": manager.get(&version, None)? }))?;
         }
+        Rvm::History => {
+            let mut history = manager.default_history()?;
+            history.reverse();
+            let default = manager
+                .get_default()
+                .ok()
+                .map(|default| default.version().clone());
+            output.json(&history)?;
+            if history.is_empty() {
+                output.text("No default version changes are recorded");
+            }
+            for (i, change) in history.iter().enumerate() {
+                output.text(format!(
+                    "{} {:<24}{}",
+                    if i == 0 && default.as_ref() == Some(&change.version) {
+                        "*"
+                    } else {
+                        " "
+                    },
+                    change.version,
+                    output::datetime(change.changed_at)
+                ));
+            }
+        }
+        Rvm::Outdated => upgrade::outdated(&manager, &std::env::current_dir()?, output)?,
+        Rvm::Upgrade {
+            channel,
+            project,
+            prune,
+        } => upgrade::upgrade(
+            &manager,
+            &std::env::current_dir()?,
+            output,
+            channel,
+            project,
+            prune,
+        )?,
         Rvm::Which(WithVersion { version }) => {
             let build = manager.get(&version, None)?;
-            println!(
+            output.text(format!(
                 "Path to the requested binary version of Resolc: {}",
                 build.local().expect("Can't happen").to_string_lossy()
+            ));
+            output.json(&build)?;
+        }
+        Rvm::Resolve {
+            sources,
+            channel,
+            install,
+        } => {
+            if let Some(path) = sources.iter().find(|path| !path.exists()) {
+                anyhow::bail!("Source path {} doesn't exist", path.display());
+            }
+            let progress = output.progress(
+                "resolve",
+                String::new(),
+                "Reading the pragmas of the sources".to_owned(),
             );
+            let resolution = manager.resolve_sources(&sources, channel, install)?;
+            progress.finish(format!(
+                "{} pragmas are satisfied",
+                resolution.pragmas.len()
+            ));
+            output.text(format!(
+                "Resolc v{} with solc v{}{}",
+                resolution.resolc,
+                resolution.solc,
+                if resolution.installed {
+                    ""
+                } else {
+                    ", run `rvm install` or pass `--install` to install it"
+                }
+            ));
+            for pragma in &resolution.pragmas {
+                output.text(format!(
+                    "  {}: pragma solidity {}",
+                    pragma.path.display(),
+                    pragma.constraint
+                ));
+            }
+            output.json(&resolution)?;
+        }
+        Rvm::Current { selector } => {
+            let selector = selector.map(|selector| selector.trim_start_matches('+').to_owned());
+            let resolution = manager.resolve(selector.as_deref(), &std::env::current_dir()?)?;
+            output.json(&resolution)?;
+            output.text(format!(
+                "Resolc v{}: {}",
+                resolution.binary.version(),
+                resolution
+                    .binary
+                    .local()
+                    .expect("resolved binaries are local")
+                    .display()
+            ));
+            output.text(format!(
+                "Selected by {} ({})",
+                resolution.selected.source, resolution.selected.selector
+            ));
+            if let rvm::Source::ToolConfig {
+                solc: Some(solc), ..
+            } = &resolution.selected.source
+            {
+                output.text(format!("Requested solc: {solc}"));
+            }
+            for candidate in resolution.shadowed {
+                output.text(format!(
+                    "Overrides {} ({})",
+                    candidate.source, candidate.selector
+                ));
+            }
+        }
+        Rvm::Run {
+            version,
+            ephemeral,
+            command,
+        } => {
+            let code = run::run(&manager, is_offline, output, &version, ephemeral, &command)?;
+            std::process::exit(code);
+        }
+        Rvm::Shims => {
+            manager.update_shims()?;
+            let dir = manager.shim_dir();
+            output.text(format!("Shims are in {}", dir.display()));
+            output.json(&json!({ "dir": dir }))?;
+        }
+        Rvm::Info(WithVersion { version }) => {
+            let info = manager.info(&version)?;
+            output.json(&info)?;
+            let mut lines = vec![
+                ("Version", info.version.to_string()),
+                ("Long version", info.long_version),
+            ];
+            lines.extend(info.commit.map(|commit| ("Commit", commit)));
+            lines.extend([
+                ("Channel", Channel::of(&info.version).to_string()),
+                (
+                    "Supported solc",
+                    format!(
+                        "{} - {}",
+                        info.first_supported_solc_version, info.last_supported_solc_version
+                    ),
+                ),
+                (
+                    "Registry",
+                    info.registry
+                        .unwrap_or_else(|| OFFICIAL_REGISTRY.to_owned()),
+                ),
+                ("URL", info.url.to_string()),
+                ("sha256", info.sha256),
+            ]);
+            match info.installation {
+                Some(installation) => lines.extend([
+                    ("Path", installation.path.display().to_string()),
+                    ("Size", output::size(installation.size)),
+                    (
+                        "Installed",
+                        installation
+                            .installed_at
+                            .map(output::date)
+                            .unwrap_or_else(|| "unknown".to_owned()),
+                    ),
+                    (
+                        "Installed from",
+                        installation
+                            .source
+                            .map(|source| source.to_string())
+                            .unwrap_or_else(|| "unknown".to_owned()),
+                    ),
+                    (
+                        "Last used",
+                        installation
+                            .last_used
+                            .map(output::date)
+                            .unwrap_or_else(|| "never".to_owned()),
+                    ),
+                    (
+                        "Verified",
+                        if installation.verified {
+                            "yes".to_owned()
+                        } else {
+                            "no, the binary doesn't match its sha256".to_owned()
+                        },
+                    ),
+                ]),
+                None => lines.push(("Path", "not installed".to_owned())),
+            }
+            lines.push((
+                "Default",
+                if info.is_default { "yes" } else { "no" }.to_owned(),
+            ));
+            for (name, value) in lines {
+                output.text(format!("{:<16}{}", format!("{name}:"), value));
+            }
+        }
+        Rvm::Lock { version, platforms } => {
+            let (path, lockfile) =
+                manager.lock(version.as_deref(), &std::env::current_dir()?, &platforms)?;
+            let platforms: Vec<String> =
+                lockfile.platforms.keys().map(ToString::to_string).collect();
+            output.text(format!(
+                "Resolc v{} is locked in {} for {}",
+                lockfile.version,
+                path.display(),
+                platforms.join(", ")
+            ));
+            output.json(&json!({ "path": path, "lockfile": lockfile }))?;
+        }
+        Rvm::Sync { from: Some(path) } => {
+            let progress = output.progress(
+                "sync",
+                path.display().to_string(),
+                "Installing the requested Resolc versions".to_owned(),
+            );
+            let binaries = manager.sync_from(&path)?;
+            progress.finish(format!(
+                "Resolc versions of {} are installed succesfully",
+                path.display()
+            ));
+            for binary in &binaries {
+                output.text(format!(
+                    "Resolc v{} is installed for {}",
+                    binary.version(),
+                    path.display()
+                ));
+            }
+            output.json(&binaries)?;
+        }
+        Rvm::Sync { from: None } => {
+            let progress = output.progress(
+                "sync",
+                LOCK_FILE_NAME.to_owned(),
+                "Installing the locked Resolc binary".to_owned(),
+            );
+            let binary = manager.sync(&std::env::current_dir()?)?;
+            progress.finish(format!(
+                "Resolc v{} is installed succesfully",
+                binary.version()
+            ));
+            output.text(format!(
+                "Resolc v{} matches {LOCK_FILE_NAME}",
+                binary.version()
+            ));
+            output.json(&binary)?;
+        }
+        Rvm::Alias(command) => alias(&manager, output, command)?,
+        Rvm::Link { name, path } => {
+            let binary = manager.link(&name, &path)?;
+            output.text(format!(
+                "Resolc v{} from {} is linked as {}",
+                binary.version(),
+                path.display(),
+                name
+            ));
+            output.json(&json!({ "name": name, "binary": binary }))?;
+        }
+        Rvm::Unlink { name } => {
+            manager.unlink(&name)?;
+            output.text(format!("{} is unlinked succesfully", name));
+            output.json(&json!({ "unlinked": name }))?;
+        }
+        Rvm::Import { scan, paths } => {
+            let report = manager.import(&paths, scan)?;
+            for (path, version) in &report.imported {
+                output.text(format!(
+                    "Resolc v{} is imported from {}",
+                    version,
+                    path.display()
+                ));
+            }
+            for (path, version) in &report.installed {
+                output.text(format!(
+                    "Resolc v{} from {} is already installed",
+                    version,
+                    path.display()
+                ));
+            }
+            for (path, version) in &report.unknown {
+                output.text(format!(
+                    "Unknown build of Resolc v{} at {}, it doesn't match any release",
+                    version,
+                    path.display()
+                ));
+            }
+            if report.imported.is_empty()
+                && report.installed.is_empty()
+                && report.unknown.is_empty()
+            {
+                output.text("No Resolc binaries found");
+            }
+            let found = |binaries: &[(PathBuf, Version)]| {
+                binaries
+                    .iter()
+                    .map(|(path, version)| json!({ "path": path, "version": version }))
+                    .collect::<Vec<_>>()
+            };
+            output.json(&json!({
+                "imported": found(&report.imported),
+                "installed": found(&report.installed),
+                "unknown": found(&report.unknown),
+            }))?;
+        }
+        Rvm::Push { version, registry } => {
+            let progress = output.progress(
+                "push",
+                version.to_string(),
+                format!("Publishing Resolc v{} to {}", version, registry),
+            );
+            manager.push(&version, &registry)?;
+            progress.finish(format!(
+                "Resolc v{} is published to {} succesfully",
+                version, registry
+            ));
+            output.json(&json!({ "pushed": version, "registry": registry }))?;
+        }
+        Rvm::Registry(_)
+        | Rvm::Mirror(_)
+        | Rvm::Bundle(_)
+        | Rvm::Serve { .. }
+        | Rvm::Env { .. }
+        | Rvm::Completions { .. } => {
+            unreachable!("handled without a version manager")
         }
     };
     Ok(())
 }
 
+fn alias(manager: &VersionManager, output: Output, command: AliasCommand) -> anyhow::Result<()> {
+    match command {
+        AliasCommand::Set { name, target } => {
+            let alias = manager.set_alias(&name, &target)?;
+            output.text(format!("{} now stands for {}", alias.name, alias.target));
+            output.json(&alias)?;
+        }
+        AliasCommand::Remove { name } => {
+            manager.remove_alias(&name)?;
+            output.text(format!("Alias {} is removed succesfully", name));
+            output.json(&json!({ "removed": name }))?;
+        }
+        AliasCommand::List => {
+            let aliases = manager.aliases()?;
+            output.json(&aliases)?;
+            if aliases.is_empty() {
+                output.text("No aliases are set");
+            }
+            let width = aliases
+                .iter()
+                .map(|alias| alias.name.len())
+                .max()
+                .unwrap_or_default();
+            for alias in aliases {
+                output.text(format!(
+                    "{:<width$}  {:<16}  {}",
+                    alias.name,
+                    alias.target,
+                    alias
+                        .version
+                        .map(|version| format!("v{version}"))
+                        .unwrap_or_else(|| "not installed".to_owned())
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn registry(output: Output, command: RegistryCommand) -> anyhow::Result<(), anyhow::Error> {
+    let mut config = Config::load()?;
+    match command {
+        RegistryCommand::Add {
+            name,
+            url,
+            priority,
+        } => {
+            let registry = Registry::new(name.clone(), url, priority)?;
+            config.add_registry(registry.clone());
+            config.save()?;
+            output.text(format!("Registry {} is added succesfully", name));
+            output.json(&registry)?;
+        }
+        RegistryCommand::Remove { name } => {
+            config.remove_registry(&name)?;
+            config.save()?;
+            output.text(format!("Registry {} is removed succesfully", name));
+            output.json(&json!({ "removed": name }))?;
+        }
+        RegistryCommand::List => {
+            let mut registries = vec![(OFFICIAL_REGISTRY.to_owned(), 0, rvm::REPO_URL.to_owned())];
+            registries.extend(
+                config
+                    .registries
+                    .into_iter()
+                    .map(|registry| (registry.name, registry.priority, registry.url.to_string())),
+            );
+            registries.sort_by_key(|(_, priority, _)| std::cmp::Reverse(*priority));
+            output.json(
+                &registries
+                    .iter()
+                    .map(|(name, priority, url)| {
+                        json!({ "name": name, "priority": priority, "url": url })
+                    })
+                    .collect::<Vec<_>>(),
+            )?;
+            for (name, priority, url) in registries {
+                output.text(format!("{name}\tpriority: {priority}\t{url}"));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn mirror(
+    is_offline: bool,
+    output: Output,
+    command: MirrorCommand,
+) -> anyhow::Result<(), anyhow::Error> {
+    match command {
+        MirrorCommand::Sync {
+            dir,
+            versions,
+            channels,
+            platforms,
+        } => {
+            if is_offline {
+                return Err(Error::CantInstallOffline.into());
+            }
+
+            let mirror = Mirror::new(&dir)?;
+            let progress = output.progress(
+                "mirror-sync",
+                dir.display().to_string(),
+                format!("Synchronizing mirror in {}", dir.display()),
+            );
+            let report = mirror.sync(&MirrorOptions {
+                specs: versions,
+                channels,
+                platforms: platforms.clone(),
+            })?;
+            progress.finish(format!(
+                "Mirror is synchronized: {} builds downloaded, {} builds verified",
+                report.downloaded.len(),
+                report.verified.len()
+            ));
+            let url = mirror.url()?;
+            output.text(format!(
+                "Release repository: {url}, add it with `rvm registry add <name> {url}`"
+            ));
+            let builds = |builds: &[(Platform, Version)]| {
+                builds
+                    .iter()
+                    .map(|(platform, version)| json!({ "platform": platform, "version": version }))
+                    .collect::<Vec<_>>()
+            };
+            output.json(&json!({
+                "downloaded": builds(&report.downloaded),
+                "verified": builds(&report.verified),
+                "url": url,
+            }))?;
+        }
+    }
+    Ok(())
+}
+
+fn bundle(output: Output, command: BundleCommand) -> anyhow::Result<(), anyhow::Error> {
+    match command {
+        BundleCommand::Create {
+            versions,
+            output: path,
+        } => {
+            let progress = output.progress(
+                "bundle-create",
+                path.display().to_string(),
+                format!("Packing {} versions of Resolc", versions.len()),
+            );
+            let manifest = Bundle::create(&versions, &path)?;
+            progress.finish(format!(
+                "Bundle with {} versions of Resolc is created in {}",
+                manifest.versions.len(),
+                path.display()
+            ));
+            output.json(&json!({ "path": path, "manifest": manifest }))?;
+        }
+        BundleCommand::Import {
+            bundle,
+            set_default,
+        } => {
+            let progress = output.progress(
+                "bundle-import",
+                bundle.display().to_string(),
+                format!("Importing the bundle {}", bundle.display()),
+            );
+            let report = Bundle::import(&bundle, set_default)?;
+            progress.finish(format!(
+                "Bundle {} is imported succesfully",
+                bundle.display()
+            ));
+            for version in &report.installed {
+                output.text(format!("Resolc v{} is installed succesfully", version));
+            }
+            for version in &report.skipped {
+                output.text(format!("Resolc v{} is already installed", version));
+            }
+            let default = report.manifest.default.filter(|_| set_default);
+            if let Some(default) = &default {
+                output.text(format!("Succesfully set Resolc v{} as default", default));
+            }
+            output.json(&json!({
+                "installed": report.installed,
+                "skipped": report.skipped,
+                "default": default,
+            }))?;
+        }
+    }
+    Ok(())
+}
+
 fn main() -> anyhow::Result<(), anyhow::Error> {
-    let rvm = Cli::parse();
-    let manager = VersionManager::new(rvm.offline).unwrap();
-    exec(rvm.offline, rvm.command, manager)
+    let matches = Cli::command().get_matches();
+    let rvm = Cli::from_arg_matches(&matches)?;
+    let output = Output {
+        format: rvm.format,
+        messages: rvm.message_format,
+    };
+    let action = matches.subcommand_name().unwrap_or_default().to_owned();
+    match run(rvm, output, &action) {
+        Err(err) if output.failed(&action, &err) => std::process::exit(1),
+        result => result,
+    }
+}
+
+fn run(rvm: Cli, output: Output, action: &str) -> anyhow::Result<(), anyhow::Error> {
+    match rvm.command {
+        Rvm::Registry(command) => registry(output, command),
+        Rvm::Mirror(command) => mirror(rvm.offline, output, command),
+        Rvm::Bundle(command) => bundle(output, command),
+        Rvm::Env {
+            shell,
+            project_version,
+        } => {
+            if project_version {
+                if let Some(project) = VersionManager::project_version(&std::env::current_dir()?)? {
+                    output.text(&project.selector);
+                    output.json(&project)?;
+                }
+            } else if let Some(shell) = shell {
+                print!("{}", env::env(shell, &VersionManager::default_shim_dir()?));
+            }
+            Ok(())
+        }
+        Rvm::Completions { shell } => {
+            print!("{}", env::completions(shell));
+            Ok(())
+        }
+        Rvm::Serve {
+            address,
+            mirror,
+            public_url,
+        } => {
+            let source = match mirror {
+                Some(dir) => serve::Source::Mirror(dir),
+                None => serve::Source::Store,
+            };
+            serve::serve(source, &address, public_url)
+        }
+        command => {
+            let manager = VersionManager::new(rvm.offline)?;
+            for registry in manager.unavailable_registries() {
+                output.warning(
+                    action,
+                    &registry.name,
+                    &format!(
+                        "registry {} is unavailable, its versions are skipped: {}",
+                        registry.name, registry.reason
+                    ),
+                );
+            }
+            for build in manager.skipped_builds() {
+                output.warning(
+                    action,
+                    &build.reference,
+                    &format!(
+                        "{} of registry {} is skipped: {}",
+                        build.reference,
+                        build.registry.as_deref().unwrap_or_default(),
+                        build.reason
+                    ),
+                );
+            }
+            exec(rvm.offline, output, command, manager)
+        }
+    }
 }
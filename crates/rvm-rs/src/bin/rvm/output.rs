@@ -0,0 +1,215 @@
+//! Human and machine-readable output of `rvm` commands
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use clap::ValueEnum;
+use indicatif::ProgressBar;
+use serde::Serialize;
+
+/// Format of command results printed to stdout.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum Format {
+    /// Human readable text
+    #[default]
+    Text,
+    /// Single JSON document, `Binary` values use the serde representation of the library
+    Json,
+}
+
+/// Format of progress messages printed to stderr.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, ValueEnum)]
+pub enum MessageFormat {
+    /// Spinners and human readable messages
+    #[default]
+    Human,
+    /// One JSON object per progress event
+    JsonLines,
+}
+
+/// Progress event emitted with `--message-format json-lines`.
+#[derive(Serialize)]
+struct Event<'a> {
+    event: &'a str,
+    action: &'a str,
+    subject: &'a str,
+    message: &'a str,
+}
+
+/// Destination of command output.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Output {
+    pub format: Format,
+    pub messages: MessageFormat,
+}
+
+impl Output {
+    /// Prints a result message, only in the text format
+    pub fn text(&self, message: impl AsRef<str>) {
+        if self.format == Format::Text {
+            println!("{}", message.as_ref());
+        }
+    }
+
+    /// Prints a result value, only in the JSON format
+    pub fn json(&self, value: &impl Serialize) -> anyhow::Result<()> {
+        if self.format == Format::Json {
+            println!("{}", serde_json::to_string_pretty(value)?);
+        }
+        Ok(())
+    }
+
+    /// Starts a long running action, e.g. a download
+    ///
+    /// # Arguments
+    ///
+    /// * `action` - machine-readable name of the action, e.g. `install`
+    /// * `subject` - what the action is performed on, e.g. a version
+    /// * `message` - human readable description of the action
+    pub fn progress(&self, action: &'static str, subject: String, message: String) -> Progress {
+        let spinner = match self.messages {
+            MessageFormat::Human => {
+                let spinner = ProgressBar::new_spinner();
+                spinner.enable_steady_tick(Duration::from_millis(150));
+                spinner.set_message(message);
+                Some(spinner)
+            }
+            MessageFormat::JsonLines => {
+                self.event("started", action, &subject, &message);
+                None
+            }
+        };
+        Progress {
+            output: *self,
+            spinner,
+            action,
+            subject,
+        }
+    }
+
+    /// Reports a failed command, as a `failed` event with `--message-format json-lines`
+    ///
+    /// Returns `false` when the error is left to be printed in the human readable form.
+    pub fn failed(&self, action: &str, error: &anyhow::Error) -> bool {
+        match self.messages {
+            MessageFormat::Human => false,
+            MessageFormat::JsonLines => {
+                self.event("failed", action, "", &format!("{error:#}"));
+                true
+            }
+        }
+    }
+
+    /// Reports a problem that does not fail the command, as a `warning` event with
+    /// `--message-format json-lines`
+    pub fn warning(&self, action: &str, subject: &str, message: &str) {
+        match self.messages {
+            MessageFormat::Human => eprintln!("Warning: {message}"),
+            MessageFormat::JsonLines => self.event("warning", action, subject, message),
+        }
+    }
+
+    fn event(&self, event: &str, action: &str, subject: &str, message: &str) {
+        let event = Event {
+            event,
+            action,
+            subject,
+            message,
+        };
+        if let Ok(line) = serde_json::to_string(&event) {
+            eprintln!("{line}");
+        }
+    }
+}
+
+/// Long running action started with [`Output::progress`].
+pub struct Progress {
+    output: Output,
+    spinner: Option<ProgressBar>,
+    action: &'static str,
+    subject: String,
+}
+
+impl Progress {
+    /// Marks the action as finished successfully
+    pub fn finish(self, message: String) {
+        match self.spinner {
+            Some(spinner) => spinner.finish_with_message(message),
+            None => self
+                .output
+                .event("finished", self.action, &self.subject, &message),
+        }
+    }
+}
+
+/// Formats the size in bytes, e.g. `12.3 MiB`
+pub fn size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{size:.1} {}", UNITS[unit])
+    }
+}
+
+/// Formats the rows as columns padded to the widest cell, the first row being the header
+pub fn table<const N: usize>(rows: &[[String; N]]) -> String {
+    let widths: Vec<usize> = (0..N)
+        .map(|column| rows.iter().map(|row| row[column].len()).max().unwrap_or(0))
+        .collect();
+    rows.iter()
+        .map(|row| {
+            let line: Vec<String> = row
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{cell:width$}"))
+                .collect();
+            line.join("  ").trim_end().to_owned()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Formats the time as an UTC date, e.g. `2025-07-18`
+pub fn date(time: SystemTime) -> String {
+    let days = time
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs() / 86_400)
+        .unwrap_or_default() as i64;
+    // Civil date from days since the epoch, see http://howardhinnant.github.io/date_algorithms.html
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z - era * 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{year:04}-{month:02}-{day:02}")
+}
+
+/// Formats the time as an UTC date and time, e.g. `2025-07-18 14:02 UTC`
+pub fn datetime(time: SystemTime) -> String {
+    let seconds = time
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or_default()
+        % 86_400;
+    format!(
+        "{} {:02}:{:02} UTC",
+        date(time),
+        seconds / 3_600,
+        seconds % 3_600 / 60
+    )
+}
@@ -0,0 +1,97 @@
+//! `rvm run`: commands with a given Resolc version first on `PATH`
+
+use std::{
+    ffi::OsString,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use rvm::{Binary, Config, Error, VersionManager, PATH_ENV, VERSION_ENV};
+
+use crate::output::Output;
+
+/// Runs the command with the selected Resolc as `resolc` first on `PATH`, returns its exit code
+///
+/// # Arguments
+///
+/// * `selector` - version, version specification or link name
+/// * `ephemeral` - install into a temporary store removed once the command exits
+pub fn run(
+    manager: &VersionManager,
+    is_offline: bool,
+    output: Output,
+    selector: &str,
+    ephemeral: bool,
+    command: &[OsString],
+) -> anyhow::Result<i32> {
+    let temp = tempfile::tempdir()?;
+    let binary = if ephemeral {
+        if is_offline {
+            return Err(Error::CantInstallOffline.into());
+        }
+        let manager = VersionManager::new_in(&temp.path().join("store"), false, &Config::load()?)?;
+        install(&manager, output, selector)?
+    } else {
+        match manager.select(selector) {
+            Ok(binary) => binary,
+            Err(Error::NotInstalled { .. } | Error::NoMatchingVersion { .. }) if !is_offline => {
+                install(manager, output, selector)?
+            }
+            Err(err) => return Err(err.into()),
+        }
+    };
+    let path = binary.local().expect("selected binaries are local");
+
+    let bin = temp.path().join("bin");
+    std::fs::create_dir(&bin)?;
+    expose(
+        path,
+        &bin.join(format!("resolc{}", std::env::consts::EXE_SUFFIX)),
+    )?;
+    let paths = std::env::var_os("PATH").unwrap_or_default();
+    let paths = std::env::join_paths(
+        std::iter::once(bin).chain(std::env::split_paths(&paths).collect::<Vec<PathBuf>>()),
+    )?;
+
+    let (program, args) = command.split_first().expect("command is required");
+    let status = Command::new(program)
+        .args(args)
+        .env("PATH", paths)
+        .env(
+            VERSION_ENV,
+            binary
+                .info()
+                .link
+                .clone()
+                .unwrap_or_else(|| binary.version().to_string()),
+        )
+        .env(PATH_ENV, path)
+        .status()?;
+    Ok(status.code().unwrap_or(-1))
+}
+
+fn install(manager: &VersionManager, output: Output, selector: &str) -> anyhow::Result<Binary> {
+    let progress = output.progress(
+        "install",
+        selector.to_owned(),
+        format!("Downloading and installing Resolc {}", selector),
+    );
+    let binary = manager.get_or_install_matching(selector)?;
+    progress.finish(format!(
+        "Resolc v{} is installed succesfully",
+        binary.version()
+    ));
+    Ok(binary)
+}
+
+/// Makes the binary available as `resolc` in the folder added to `PATH`
+fn expose(binary: &Path, resolc: &Path) -> std::io::Result<()> {
+    #[cfg(unix)]
+    {
+        std::os::unix::fs::symlink(binary, resolc)
+    }
+    #[cfg(not(unix))]
+    {
+        std::fs::hard_link(binary, resolc).or_else(|_| std::fs::copy(binary, resolc).map(|_| ()))
+    }
+}
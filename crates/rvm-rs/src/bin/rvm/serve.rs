@@ -0,0 +1,175 @@
+//! HTTP server exposing local builds as a release repository
+
+use std::{
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+use rvm::{Channel, Mirror, Platform, ServedReleases};
+use semver::Version;
+use tiny_http::{Header, Request, Response, Server};
+use url::Url;
+
+const WORKERS: usize = 4;
+
+/// Request counters exposed on `/metrics`.
+#[derive(Default)]
+struct Metrics {
+    requests: AtomicU64,
+    lists: AtomicU64,
+    binaries: AtomicU64,
+    not_found: AtomicU64,
+    errors: AtomicU64,
+}
+
+impl Metrics {
+    fn render(&self) -> String {
+        [
+            ("rvm_requests_total", &self.requests),
+            ("rvm_list_requests_total", &self.lists),
+            ("rvm_binary_requests_total", &self.binaries),
+            ("rvm_not_found_total", &self.not_found),
+            ("rvm_errors_total", &self.errors),
+        ]
+        .iter()
+        .map(|(name, counter)| format!("{name} {}\n", counter.load(Ordering::Relaxed)))
+        .collect()
+    }
+}
+
+/// Where served builds come from.
+pub enum Source {
+    /// Versions installed in the local store
+    Store,
+    /// Mirror created by `rvm mirror sync`
+    Mirror(PathBuf),
+}
+
+impl Source {
+    fn releases(&self, platform: Platform) -> anyhow::Result<Option<ServedReleases>> {
+        match self {
+            Source::Store if platform == Platform::get()? => {
+                Ok(Some(ServedReleases::from_store()?))
+            }
+            Source::Store => Ok(None),
+            Source::Mirror(dir) => Ok(Some(ServedReleases::from_mirror(
+                &Mirror::new(dir)?,
+                platform,
+            )?)),
+        }
+    }
+}
+
+/// Serves release lists and binaries until the process is stopped
+pub fn serve(source: Source, address: &str, public_url: Option<Url>) -> anyhow::Result<()> {
+    let server = Arc::new(Server::http(address).map_err(|err| anyhow::anyhow!(err))?);
+    let source = Arc::new(source);
+    let metrics = Arc::new(Metrics::default());
+    println!(
+        "Serving Resolc releases on http://{}/",
+        server.server_addr()
+    );
+
+    let workers: Vec<_> = (0..WORKERS)
+        .map(|_| {
+            let server = server.clone();
+            let source = source.clone();
+            let metrics = metrics.clone();
+            let public_url = public_url.clone();
+            std::thread::spawn(move || {
+                for request in server.incoming_requests() {
+                    metrics.requests.fetch_add(1, Ordering::Relaxed);
+                    if let Err(err) = handle(request, &source, &metrics, public_url.as_ref()) {
+                        metrics.errors.fetch_add(1, Ordering::Relaxed);
+                        eprintln!("rvm: error: {err}");
+                    }
+                }
+            })
+        })
+        .collect();
+    for worker in workers {
+        let _ = worker.join();
+    }
+    Ok(())
+}
+
+fn handle(
+    request: Request,
+    source: &Source,
+    metrics: &Metrics,
+    public_url: Option<&Url>,
+) -> anyhow::Result<()> {
+    // `Platform::download_url` produces paths with duplicated slashes, so empty segments are skipped.
+    let path = request
+        .url()
+        .split('?')
+        .next()
+        .unwrap_or_default()
+        .to_owned();
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+    let list = |platform: &str, channel: Channel| -> anyhow::Result<Option<String>> {
+        let Ok(platform) = platform.parse::<Platform>() else {
+            return Ok(None);
+        };
+        let Some(releases) = source.releases(platform)? else {
+            return Ok(None);
+        };
+        let base = match public_url {
+            Some(url) => url.clone(),
+            None => base_url(&request)?,
+        };
+        metrics.lists.fetch_add(1, Ordering::Relaxed);
+        Ok(Some(releases.list(channel, &base)?))
+    };
+
+    let response = match segments.as_slice() {
+        ["health"] => Some(Response::from_string("ok").boxed()),
+        ["metrics"] => Some(Response::from_string(metrics.render()).boxed()),
+        [platform, "list.json"] => list(platform, Channel::Stable)?.map(json),
+        ["nightly", platform, "list.json"] => list(platform, Channel::Nightly)?.map(json),
+        [platform, version, name] => match (platform.parse(), version.parse::<Version>()) {
+            (Ok(platform), Ok(version)) => {
+                let releases = source.releases(platform)?;
+                match releases.as_ref().and_then(|r| r.binary(&version, name)) {
+                    Some(path) => {
+                        metrics.binaries.fetch_add(1, Ordering::Relaxed);
+                        Some(Response::from_file(std::fs::File::open(path)?).boxed())
+                    }
+                    None => None,
+                }
+            }
+            _ => None,
+        },
+        _ => None,
+    };
+
+    let response = response.unwrap_or_else(|| {
+        metrics.not_found.fetch_add(1, Ordering::Relaxed);
+        Response::from_string("not found")
+            .with_status_code(404)
+            .boxed()
+    });
+    request.respond(response)?;
+    Ok(())
+}
+
+fn json(body: String) -> tiny_http::ResponseBox {
+    Response::from_string(body)
+        .with_header(Header::from_bytes("Content-Type", "application/json").expect("valid header"))
+        .boxed()
+}
+
+/// Base URL the client used to reach the server
+fn base_url(request: &Request) -> anyhow::Result<Url> {
+    let host = request
+        .headers()
+        .iter()
+        .find(|header| header.field.equiv("Host"))
+        .map(|header| header.value.to_string())
+        .ok_or_else(|| anyhow::anyhow!("request without a Host header"))?;
+    Ok(Url::parse(&format!("http://{host}/"))?)
+}
@@ -0,0 +1,107 @@
+//! `rvm outdated` and `rvm upgrade`
+
+use std::path::Path;
+
+use rvm::{Channel, UpgradeTarget, VersionManager};
+
+use crate::output::{table, Format, Output};
+
+/// Prints the installed, default and project versions with the newer releases of their channels
+pub fn outdated(manager: &VersionManager, dir: &Path, output: Output) -> anyhow::Result<()> {
+    let versions = manager.outdated(dir)?;
+    if output.format == Format::Json {
+        return output.json(&versions);
+    }
+    if versions.is_empty() {
+        output.text("No Resolc versions are installed");
+        return Ok(());
+    }
+
+    let mut rows = vec![[
+        String::new(),
+        "VERSION".to_owned(),
+        "CHANNEL".to_owned(),
+        "COMPATIBLE".to_owned(),
+        "LATEST".to_owned(),
+        "STATUS".to_owned(),
+    ]];
+    for version in &versions {
+        let status = match (&version.latest, version.installed) {
+            (_, false) => "not installed",
+            (Some(_), true) => "outdated",
+            (None, true) => "up to date",
+        };
+        rows.push([
+            format!(
+                "{}{}",
+                if version.is_default { "*" } else { "" },
+                if version.project.is_some() { ">" } else { "" }
+            ),
+            version.version.to_string(),
+            Channel::of(&version.version).to_string(),
+            version
+                .compatible
+                .as_ref()
+                .map(ToString::to_string)
+                .unwrap_or_else(|| "-".to_owned()),
+            version
+                .latest
+                .as_ref()
+                .map(ToString::to_string)
+                .unwrap_or_else(|| "-".to_owned()),
+            status.to_owned(),
+        ]);
+    }
+
+    output.text(table(&rows));
+    Ok(())
+}
+
+/// Installs the newest release of the channel and switches the default or the project to it
+pub fn upgrade(
+    manager: &VersionManager,
+    dir: &Path,
+    output: Output,
+    channel: Option<Channel>,
+    project: bool,
+    prune: bool,
+) -> anyhow::Result<()> {
+    let target = if project {
+        UpgradeTarget::Project
+    } else {
+        UpgradeTarget::Default
+    };
+    let progress = output.progress(
+        "upgrade",
+        channel
+            .map(|channel| channel.to_string())
+            .unwrap_or_default(),
+        "Downloading and installing the newest Resolc".to_owned(),
+    );
+    let upgrade = manager.upgrade(channel, target, dir, prune)?;
+    let to = upgrade.to.version();
+    let message = match (&upgrade.from, &upgrade.project) {
+        (Some(from), _) if from == to => format!("Resolc v{to} is already the newest version"),
+        (from, Some(path)) => format!(
+            "{} now pins Resolc v{to}{}",
+            path.display(),
+            from.as_ref()
+                .map(|from| format!(" instead of v{from}"))
+                .unwrap_or_default()
+        ),
+        (from, None) => format!(
+            "Resolc v{to} is now the default{}",
+            from.as_ref()
+                .map(|from| format!(" instead of v{from}"))
+                .unwrap_or_default()
+        ),
+    };
+    progress.finish(format!("Resolc v{to} is installed succesfully"));
+    output.text(message);
+    if upgrade.pruned {
+        if let Some(from) = &upgrade.from {
+            output.text(format!("Resolc v{from} is removed succesfully"));
+        }
+    }
+    output.json(&upgrade)
+}
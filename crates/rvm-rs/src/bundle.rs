@@ -0,0 +1,327 @@
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{Read, Write},
+    path::{Path, PathBuf},
+};
+
+use semver::Version;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    constants::Platform,
+    errors::Error,
+    fs::{DataDir, FsPaths, BUILD_FILE_NAME},
+    releases::{sha256_reader, Build},
+};
+
+const MANIFEST_FILE_NAME: &str = "manifest.json";
+
+/// Content of an offline bundle, stored as `manifest.json` at the root of the archive.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BundleManifest {
+    /// Platform the bundled binaries are built for
+    pub platform: Platform,
+    /// Default version of the machine the bundle was created on, if it's bundled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<Version>,
+    /// Bundled versions
+    pub versions: Vec<BundleEntry>,
+}
+
+/// Version stored in an offline bundle as `<version>/build.json` and `<version>/<binary>`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BundleEntry {
+    /// Resolc version
+    pub version: Version,
+    /// Path of the binary inside the archive
+    pub binary: String,
+    /// sha256 of the binary
+    pub sha256: String,
+}
+
+/// Outcome of a bundle import.
+#[derive(Debug)]
+pub struct ImportReport {
+    /// Manifest of the imported bundle
+    pub manifest: BundleManifest,
+    /// Versions that were installed
+    pub installed: Vec<Version>,
+    /// Versions that were installed already
+    pub skipped: Vec<Version>,
+}
+
+/// Offline bundles of installed versions, `tar` archives compressed with `zstd`.
+pub struct Bundle;
+
+impl Bundle {
+    /// Packs installed versions into a bundle at `output`
+    pub fn create(versions: &[Version], output: &Path) -> Result<BundleManifest, Error> {
+        create(&DataDir::new()?, versions, output)
+    }
+
+    /// Verifies and installs every version of the bundle at `input`
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - path to the bundle
+    /// * `set_default` - also use the default version of the bundle as the default version
+    pub fn import(input: &Path, set_default: bool) -> Result<ImportReport, Error> {
+        import(&DataDir::new()?, input, set_default)
+    }
+}
+
+fn append(
+    archive: &mut tar::Builder<impl Write>,
+    path: &str,
+    data: &[u8],
+    mode: u32,
+) -> Result<(), Error> {
+    append_reader(archive, path, data, data.len() as u64, mode)
+}
+
+fn append_reader(
+    archive: &mut tar::Builder<impl Write>,
+    path: &str,
+    data: impl Read,
+    size: u64,
+    mode: u32,
+) -> Result<(), Error> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(size);
+    header.set_mode(mode);
+    header.set_cksum();
+    archive.append_data(&mut header, path, data)?;
+    Ok(())
+}
+
+/// Fails unless the sha256 of the binary matches the expected one
+fn verify(expected: &str, actual: String) -> Result<(), Error> {
+    if actual.eq_ignore_ascii_case(expected) {
+        Ok(())
+    } else {
+        Err(Error::ChecksumValidationError {
+            expected: expected.to_owned(),
+            actual,
+        })
+    }
+}
+
+pub(crate) fn create(
+    fs: &dyn FsPaths,
+    versions: &[Version],
+    output: &Path,
+) -> Result<BundleManifest, Error> {
+    let default = fs.get_default_version().ok();
+    let mut manifest = BundleManifest {
+        platform: Platform::get()?,
+        default: default.filter(|default| versions.contains(default)),
+        versions: Vec::new(),
+    };
+
+    let file = fs::File::create(output)?;
+    let mut archive = tar::Builder::new(zstd::Encoder::new(file, 0)?);
+    for version in versions {
+        let build = fs.installed_build(version)?;
+        // Binaries are streamed from the store rather than loaded into memory.
+        let path = fs.path().join(version.to_string()).join(&build.name);
+        verify(&build.sha256, sha256_reader(&mut fs::File::open(&path)?)?)?;
+
+        let binary_path = format!("{version}/{}", build.name);
+        let binary = fs::File::open(&path)?;
+        let size = binary.metadata()?.len();
+        append_reader(&mut archive, &binary_path, binary, size, 0o755)?;
+        append(
+            &mut archive,
+            &format!("{version}/{BUILD_FILE_NAME}"),
+            &serde_json::to_vec(&build)?,
+            0o644,
+        )?;
+        manifest.versions.push(BundleEntry {
+            version: version.clone(),
+            binary: binary_path,
+            sha256: build.sha256.clone(),
+        });
+    }
+    append(
+        &mut archive,
+        MANIFEST_FILE_NAME,
+        &serde_json::to_vec_pretty(&manifest)?,
+        0o644,
+    )?;
+    archive.into_inner()?.finish()?.flush()?;
+
+    Ok(manifest)
+}
+
+pub(crate) fn import(
+    fs: &dyn FsPaths,
+    input: &Path,
+    set_default: bool,
+) -> Result<ImportReport, Error> {
+    // The manifest comes last, so binaries are staged next to the store while the archive is streamed,
+    // and only the small JSON files are kept in memory.
+    let staging = tempfile::tempdir_in(fs.path())?;
+    let mut files = BTreeMap::new();
+    let mut binaries: BTreeMap<String, (PathBuf, String)> = BTreeMap::new();
+    let mut archive = tar::Archive::new(zstd::Decoder::new(fs::File::open(input)?)?);
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let path = entry.path()?.to_string_lossy().into_owned();
+        if path.ends_with(".json") {
+            let mut data = Vec::new();
+            entry.read_to_end(&mut data)?;
+            files.insert(path, data);
+        } else {
+            let staged = staging.path().join(binaries.len().to_string());
+            std::io::copy(&mut entry, &mut fs::File::create(&staged)?)?;
+            let actual = sha256_reader(&mut fs::File::open(&staged)?)?;
+            binaries.insert(path, (staged, actual));
+        }
+    }
+
+    let missing = |path: &str| Error::InvalidBundle {
+        reason: format!("{path} is missing"),
+    };
+    let manifest: BundleManifest = serde_json::from_slice(
+        files
+            .get(MANIFEST_FILE_NAME)
+            .ok_or_else(|| missing(MANIFEST_FILE_NAME))?,
+    )?;
+    let platform = Platform::get()?;
+    if manifest.platform != platform {
+        return Err(Error::InvalidBundle {
+            reason: format!(
+                "bundle is created for {}, current platform is {platform}",
+                manifest.platform
+            ),
+        });
+    }
+
+    let mut report = ImportReport {
+        manifest,
+        installed: Vec::new(),
+        skipped: Vec::new(),
+    };
+    for entry in &report.manifest.versions {
+        let build_path = format!("{}/{BUILD_FILE_NAME}", entry.version);
+        let build: Build =
+            serde_json::from_slice(files.get(&build_path).ok_or_else(|| missing(&build_path))?)?;
+        let (binary, actual) = binaries
+            .get(&entry.binary)
+            .ok_or_else(|| missing(&entry.binary))?;
+
+        verify(&entry.sha256, actual.clone())?;
+        verify(&build.sha256, actual.clone())?;
+
+        if fs.installed_build(&entry.version).is_ok() {
+            report.skipped.push(entry.version.clone());
+            continue;
+        }
+        fs.install_version_from(&build, &mut fs::File::open(binary)?)?;
+        report.installed.push(entry.version.clone());
+    }
+
+    if set_default {
+        if let Some(default) = &report.manifest.default {
+            fs.set_default_version(default)?;
+        }
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod test {
+    use semver::Version;
+
+    use super::{append, create, import};
+    use crate::{
+        errors::Error,
+        fs::FsPaths,
+        releases::{sha256, Build},
+        test::TempDir,
+    };
+
+    fn install(store: &TempDir, version: &str, binary: &[u8]) -> Version {
+        let build: Build = serde_json::from_value(serde_json::json!({
+            "name": "resolc",
+            "version": version,
+            "longVersion": format!("{version}+commit.0000000"),
+            "url": "https://example.com/resolc",
+            "sha256": sha256(binary),
+            "firstSolcVersion": "0.8.0",
+            "lastSolcVersion": "0.8.30"
+        }))
+        .unwrap();
+        store.install_version(&build, binary).unwrap();
+        build.version
+    }
+
+    #[test]
+    fn create_and_import() {
+        let source = TempDir::new().unwrap();
+        let first = install(&source, "0.3.0", b"first");
+        let second = install(&source, "0.4.0", b"second");
+        source.set_default_version(&second).unwrap();
+
+        let output = tempfile::tempdir().unwrap();
+        let bundle = output.path().join("bundle.tar.zst");
+        let manifest = create(&source, &[first.clone(), second.clone()], &bundle).unwrap();
+        assert_eq!(manifest.default, Some(second.clone()));
+
+        let target = TempDir::new().unwrap();
+        install(&target, "0.3.0", b"first");
+        let report = import(&target, &bundle, true).unwrap();
+        assert_eq!(report.installed, vec![second.clone()]);
+        assert_eq!(report.skipped, vec![first]);
+        assert_eq!(target.get_default_version().unwrap(), second);
+        assert_eq!(
+            std::fs::read(target.path().join("0.4.0").join("resolc")).unwrap(),
+            b"second"
+        );
+        // Binaries are staged in the store while importing, nothing is left behind.
+        assert!(std::fs::read_dir(target.path()).unwrap().all(|entry| !entry
+            .unwrap()
+            .file_name()
+            .to_string_lossy()
+            .starts_with(".tmp")));
+    }
+
+    #[test]
+    fn rejects_tampered_binary() {
+        let source = TempDir::new().unwrap();
+        let version = install(&source, "0.4.0", b"binary");
+        let output = tempfile::tempdir().unwrap();
+        let bundle = output.path().join("bundle.tar.zst");
+        let mut manifest = create(&source, std::slice::from_ref(&version), &bundle).unwrap();
+
+        std::fs::write(source.path().join("0.4.0").join("resolc"), b"tampered").unwrap();
+        assert!(create(&source, &[version], &bundle).is_err());
+
+        manifest.versions[0].sha256 = sha256(b"tampered");
+        let file = std::fs::File::create(&bundle).unwrap();
+        let mut archive = tar::Builder::new(zstd::Encoder::new(file, 0).unwrap());
+        append(&mut archive, "0.4.0/resolc", b"tampered", 0o755).unwrap();
+        append(
+            &mut archive,
+            "0.4.0/build.json",
+            &std::fs::read(source.path().join("0.4.0").join("build.json")).unwrap(),
+            0o644,
+        )
+        .unwrap();
+        append(
+            &mut archive,
+            "manifest.json",
+            &serde_json::to_vec(&manifest).unwrap(),
+            0o644,
+        )
+        .unwrap();
+        archive.into_inner().unwrap().finish().unwrap();
+
+        let target = TempDir::new().unwrap();
+        let err = import(&target, &bundle, false).unwrap_err();
+        assert!(matches!(err, Error::ChecksumValidationError { .. }));
+        assert!(target.installed_versions().unwrap().is_empty());
+    }
+}
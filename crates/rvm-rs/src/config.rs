@@ -0,0 +1,124 @@
+use std::collections::BTreeMap;
+
+use semver::Version;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    errors::Error,
+    fs::{DataDir, FsPaths},
+    local,
+    pragma::RESOLVE_SOURCES_ENV,
+    registry::Registry,
+    spec::VersionSpec,
+};
+
+/// Environment variable that enables installing missing versions from the `resolc` wrapper, `1` or `true`.
+pub const AUTO_INSTALL_ENV: &str = "RVM_AUTO_INSTALL";
+
+/// User configuration of the version manager.
+///
+/// Stored as `config.json` in the installation folder.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Config {
+    /// Additional release registries, the official one is always present and isn't listed here.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub registries: Vec<Registry>,
+    /// Install versions requested from the `resolc` wrapper that are missing instead of failing
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub auto_install: bool,
+    /// Names for version specifications, e.g. `prod` for `0.3.0`, usable wherever a version is accepted
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub aliases: BTreeMap<String, String>,
+    /// Read the Resolc version from `foundry.toml` and hardhat configuration of projects
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub project_configs: bool,
+    /// Pick the version from the `pragma solidity` constraints of the sources compiled by the `resolc` wrapper
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub resolve_sources: bool,
+}
+
+/// Alias of a version specification with the installed version it selects.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Alias {
+    /// Name of the alias
+    pub name: String,
+    /// Version specification the alias stands for
+    pub target: String,
+    /// Newest installed version matching the specification
+    pub version: Option<Version>,
+}
+
+impl Config {
+    /// Load configuration from the default installation folder
+    pub fn load() -> Result<Self, Error> {
+        DataDir::new()?.read_config()
+    }
+
+    /// Store configuration in the default installation folder
+    pub fn save(&self) -> Result<(), Error> {
+        DataDir::new()?.write_config(self)
+    }
+
+    /// Whether the `resolc` wrapper installs missing versions, either set in the configuration
+    /// or with the `RVM_AUTO_INSTALL` environment variable
+    pub fn auto_install(&self) -> bool {
+        match std::env::var(AUTO_INSTALL_ENV) {
+            Ok(value) if !value.is_empty() => matches!(value.as_str(), "1" | "true"),
+            _ => self.auto_install,
+        }
+    }
+
+    /// Whether the `resolc` wrapper picks the version from the pragmas of the compiled sources, either set in the
+    /// configuration or with the `RVM_RESOLVE_SOURCES` environment variable
+    pub fn resolve_sources(&self) -> bool {
+        match std::env::var(RESOLVE_SOURCES_ENV) {
+            Ok(value) if !value.is_empty() => matches!(value.as_str(), "1" | "true"),
+            _ => self.resolve_sources,
+        }
+    }
+
+    /// Adds a new registry, replacing the registry with the same name if it's present
+    pub fn add_registry(&mut self, registry: Registry) {
+        self.registries.retain(|item| item.name != registry.name);
+        self.registries.push(registry);
+    }
+
+    /// Sets the alias to the version specification, replacing the previous target
+    ///
+    /// Alias names follow the rules of link names, so they are never ambiguous with versions.
+    pub fn set_alias(&mut self, name: &str, target: &VersionSpec) -> Result<(), Error> {
+        if !local::is_valid_name(name) {
+            return Err(Error::InvalidAliasName {
+                name: name.to_owned(),
+            });
+        }
+        self.aliases.insert(name.to_owned(), target.to_string());
+        Ok(())
+    }
+
+    /// Removes the alias by name, returns its target
+    pub fn remove_alias(&mut self, name: &str) -> Result<String, Error> {
+        self.aliases
+            .remove(name)
+            .ok_or_else(|| Error::UnknownAlias {
+                name: name.to_owned(),
+            })
+    }
+
+    /// Version specification the alias stands for, `None` if there's no such alias
+    pub fn alias(&self, name: &str) -> Option<VersionSpec> {
+        self.aliases.get(name)?.parse().ok()
+    }
+
+    /// Removes registry by name
+    pub fn remove_registry(&mut self, name: &str) -> Result<Registry, Error> {
+        let position = self
+            .registries
+            .iter()
+            .position(|item| item.name == name)
+            .ok_or_else(|| Error::UnknownRegistry {
+                name: name.to_owned(),
+            })?;
+        Ok(self.registries.remove(position))
+    }
+}
@@ -1,4 +1,5 @@
 use semver::Version;
+use serde::{Deserialize, Serialize};
 
 use crate::errors::Error;
 
@@ -8,15 +9,24 @@ pub const REPO_URL: &str = "https://paritytech.github.io/resolc-bin/";
 /// Minimum supported `solc` version.
 pub(crate) const MIN_VERSION: Version = semver::Version::new(0, 8, 0);
 
-#[derive(Eq, PartialEq, PartialOrd, Ord)]
-pub(crate) enum Platform {
+/// Platforms Resolc binaries are released for.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Platform {
+    /// Linux x86_64 (MUSL)
     Linux,
+    /// MacOS (universal)
     Macos,
+    /// Windows x86_64
     Windows,
 }
 
 impl Platform {
-    pub(crate) fn get() -> Result<Self, Error> {
+    /// Every supported platform
+    pub const ALL: [Platform; 3] = [Platform::Linux, Platform::Macos, Platform::Windows];
+
+    /// Returns the platform of the current system
+    pub fn get() -> Result<Self, Error> {
         let platform = match (std::env::consts::OS, std::env::consts::ARCH) {
             ("linux", "x86_64") => Self::Linux,
             ("macos", "aarch64") | ("macos", "x86_64") => Self::Macos,
@@ -31,12 +41,27 @@ impl Platform {
         Ok(platform)
     }
 
-    pub(crate) fn download_url(&self, nightly: bool) -> Result<url::Url, Error> {
-        let platform_path = match self {
+    /// Platform name as used in release paths
+    pub fn name(&self) -> &'static str {
+        match self {
             Platform::Linux => "linux",
             Platform::Macos => "macos",
             Platform::Windows => "windows",
+        }
+    }
+
+    /// URL of the release list in a repository with the same layout as [`REPO_URL`]
+    pub(crate) fn list_url(&self, repository: &url::Url, nightly: bool) -> Result<url::Url, Error> {
+        let path = if nightly {
+            format!("nightly/{}/list.json", self.name())
+        } else {
+            format!("{}/list.json", self.name())
         };
+        repository.join(&path).map_err(Into::into)
+    }
+
+    pub(crate) fn download_url(&self, nightly: bool) -> Result<url::Url, Error> {
+        let platform_path = self.name();
         let url = if nightly {
             format!("{REPO_URL}/nightly/{platform_path}/list.json")
         } else {
@@ -46,3 +71,20 @@ impl Platform {
         Ok(url)
     }
 }
+
+impl std::str::FromStr for Platform {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Platform::ALL
+            .into_iter()
+            .find(|platform| platform.name() == s)
+            .ok_or_else(|| Error::UnknownPlatform { name: s.to_owned() })
+    }
+}
+
+impl std::fmt::Display for Platform {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
@@ -13,6 +13,8 @@ pub enum Error {
     NoVersionsInstalled,
     #[error("Unknown version of Resolc v{}.", version)]
     UnknownVersion { version: Version },
+    #[error("Unknown version of Resolc v{} in registry \"{}\".", version, registry)]
+    UnknownRegistryVersion { registry: String, version: Version },
     #[error("Version of Resolc v{} is not installed.", version)]
     NotInstalled { version: Version },
     #[error(
@@ -30,8 +32,105 @@ pub enum Error {
         resolc_version: Version,
         supported_range: semver::VersionReq,
     },
+    #[error("Unknown registry \"{name}\"")]
+    UnknownRegistry { name: String },
+    #[error("Registry name \"{name}\" is reserved")]
+    ReservedRegistryName { name: String },
+    #[error("Invalid registry name \"{name}\", it can't be empty or contain `:` and whitespace")]
+    InvalidRegistryName { name: String },
+    #[error("Registry \"{name}\" doesn't support publishing, only OCI registries do")]
+    PushNotSupported { name: String },
+    #[error("Registry \"{registry}\" has a single release list, it has no builds for {platform}")]
+    NoPlatformReleases {
+        registry: String,
+        platform: crate::Platform,
+    },
+    #[error("Invalid OCI registry URL {url}, expected `oci://<host>/<repository>`")]
+    InvalidOciUrl { url: String },
+    #[error("Invalid Resolc artifact {reference}: {reason}")]
+    InvalidOciArtifact { reference: String, reason: String },
+    #[error("OCI registry authentication failed: {reason}")]
+    OciAuthentication { reason: String },
     #[error("Unsupported platform {os}_{target}")]
     PlatformNotSupported { os: String, target: String },
+    #[error("Unknown platform \"{name}\", expected one of: linux, macos, windows")]
+    UnknownPlatform { name: String },
+    #[error("Path {} can't be used as a URL", path.display())]
+    InvalidPath { path: std::path::PathBuf },
+    #[error("{} is not a Resolc binary, `--version` didn't report a Resolc version", path.display())]
+    UnrecognizedBinary { path: std::path::PathBuf },
+    #[error("Invalid link name \"{name}\", it has to start with a letter, contain only letters, digits, `-` and `_` and can't be a version")]
+    InvalidLinkName { name: String },
+    #[error("No linked binary named \"{name}\"")]
+    UnknownLink { name: String },
+    #[error("Invalid alias name \"{name}\", it has to start with a letter, contain only letters, digits, `-` and `_` and can't be a version")]
+    InvalidAliasName { name: String },
+    #[error("No alias named \"{name}\"")]
+    UnknownAlias { name: String },
+    #[error("\"{name}\" is already used by a linked binary or an alias")]
+    NameTaken { name: String },
+    #[error("Invalid bundle: {reason}")]
+    InvalidBundle { reason: String },
+    #[error("Invalid file URL {url}")]
+    InvalidFileUrl { url: url::Url },
+    #[error("Invalid version specification \"{spec}\"")]
+    InvalidVersionSpec { spec: String },
+    #[error("No installed version of Resolc matches \"{spec}\"")]
+    NoMatchingVersion { spec: String },
+    #[error("No previous default version of Resolc is recorded")]
+    NoPreviousDefault,
+    #[error("No `.resolc-version` project file found in the current directory or its parents")]
+    NoProjectFile,
+    #[error("No Resolc releases in the {channel} channel")]
+    NoReleasesInChannel { channel: crate::Channel },
+    #[error(
+        "No `rvm.lock` found in the current directory or its parents, create one with `rvm lock`"
+    )]
+    NoLockfile,
+    #[error("Invalid lockfile {}: {reason}", path.display())]
+    InvalidLockfile {
+        path: std::path::PathBuf,
+        reason: String,
+    },
+    #[error("Resolc v{version} is already installed from registry {installed}, run `rvm remove {version}` to install it from {requested}")]
+    InstalledFromOtherRegistry {
+        version: Version,
+        installed: String,
+        requested: String,
+    },
+    #[error("Invalid `pragma solidity {constraint}` in {}", path.display())]
+    InvalidPragma {
+        path: std::path::PathBuf,
+        constraint: String,
+    },
+    #[error("No Resolc release supports a `solc` version satisfying every pragma of the sources")]
+    NoCompatibleCompiler,
+    #[error("Invalid project configuration {}: {reason}", path.display())]
+    InvalidProjectConfig {
+        path: std::path::PathBuf,
+        reason: String,
+    },
+    #[error("Resolc v{version} isn't locked for {platform}, run `rvm lock --platform {platform}` on a machine with network access")]
+    PlatformNotLocked {
+        version: Version,
+        platform: crate::Platform,
+    },
+    #[error("Installed Resolc v{version} doesn't match the lockfile: expected sha256 {expected}, got {actual}")]
+    LockfileMismatch {
+        version: Version,
+        expected: String,
+        actual: String,
+    },
+    #[error("The project is locked by {}, update it with `rvm lock <version>`", path.display())]
+    ProjectLocked { path: std::path::PathBuf },
+    #[error("Linked binary \"{name}\" can't be locked, it isn't a release")]
+    CantLockLink { name: String },
+    #[error("Can't use Resolc \"{selector}\" selected by {origin}: {reason}")]
+    SelectionFailed {
+        selector: String,
+        origin: String,
+        reason: Box<Error>,
+    },
     #[error(transparent)]
     SemverError(#[from] semver::Error),
     #[error(transparent)]
@@ -1,21 +1,43 @@
 use std::{
     fs,
-    io::{ErrorKind, Write},
+    io::{ErrorKind, Read, Write},
     path::{Path, PathBuf},
-    sync::OnceLock,
+    time::SystemTime,
 };
 
 use semver::Version;
 
-use crate::{errors::Error, Build};
+use crate::{
+    config::Config,
+    errors::Error,
+    history::{self, DefaultChange, HISTORY_FILE_NAME},
+    index::StoreIndex,
+    releases::sha256_reader,
+    shims,
+    usage::{self, InstallMetadata},
+    Build,
+};
+
+pub(crate) const BUILD_FILE_NAME: &str = "build.json";
+const CONFIG_FILE_NAME: &str = "config.json";
+const INDEX_FILE_NAME: &str = ".index.json";
 
-const BUILD_FILE_NAME: &str = "build.json";
+/// Environment variable that overrides the location of the store.
+pub const HOME_ENV: &str = "RVM_HOME";
 
 /// Trait to store and retrieve binaries and their metadata from the filesystem.
 ///
 /// global default version of Resolc is stored in `.default_version` in the installation folder.
 ///
+/// user configuration is stored in `config.json` in the installation folder.
+///
+/// summary of the installation folder used by the `resolc` wrapper is stored in `.index.json`, see [`StoreIndex`].
+///
+/// shims running the `resolc` wrapper as `resolc` and `resolc-<version>` are kept in `<installation_folder>/bin`.
+///
 /// each Resolc version will installed into `<installation_folder>/<binary version >/<binary|build.json>`
+///
+/// external binaries registered with `rvm link` only have their metadata stored in `<installation_folder>/<link name>/build.json`
 pub(crate) trait FsPaths {
     fn new() -> Result<Self, Error>
     where
@@ -24,9 +46,8 @@ pub(crate) trait FsPaths {
     /// Path to the storage folder
     fn path(&self) -> &Path;
 
-    fn default_version_path(&self) -> &'static Path {
-        static ONCE: OnceLock<PathBuf> = OnceLock::new();
-        ONCE.get_or_init(|| self.path().join(".default_version"))
+    fn default_version_path(&self) -> PathBuf {
+        self.path().join(".default_version")
     }
 
     /// installs the provided binary into `<Self::path>/<binary version>/<stored artifacts>`
@@ -35,38 +56,63 @@ pub(crate) trait FsPaths {
     /// * `binary` - binary itself
     /// * `build` - binary metadata from the releases file.
     fn install_version(&self, build: &Build, binary_blob: &[u8]) -> Result<(), Error> {
-        match self.install_inner(build, binary_blob) {
-            ok @ Ok(_) => ok,
-            Err(Error::IoError(err)) if err.kind() == ErrorKind::AlreadyExists => Ok(()),
-            e => e,
+        self.install_version_from(build, &mut &binary_blob[..])
+    }
+
+    /// Installs the binary like [`FsPaths::install_version`], streaming it from the reader
+    fn install_version_from(&self, build: &Build, binary: &mut dyn Read) -> Result<(), Error> {
+        match self.install_inner(build, binary) {
+            Ok(_) => {}
+            Err(Error::IoError(err)) if err.kind() == ErrorKind::AlreadyExists => {}
+            e => return e,
         }
+        self.update_index()
     }
 
-    fn install_inner(&self, build: &Build, binary_blob: &[u8]) -> Result<(), Error> {
+    /// Streams the binary into a `.part` file that is only renamed once it matches the sha256 of the build.
+    ///
+    /// The metadata is written last, so an interrupted installation never looks installed.
+    fn install_inner(&self, build: &Build, binary: &mut dyn Read) -> Result<(), Error> {
         let version = &build.version;
-        let binary_path = &build.name;
         let folder = self.path().join(version.to_string());
-        match self.create_lock_file(version) {
-            Ok(_) => {}
+        let _lock_file = match self.create_lock_file(version) {
+            Ok(lock_file) => lock_file,
             Err(Error::IoError(err)) if err.kind() == ErrorKind::AlreadyExists => {
                 return Ok(());
             }
             Err(e) => return Err(e),
         };
+        let path = folder.join(&build.name);
+        if path.exists() {
+            return Ok(());
+        }
 
         fs::create_dir_all(&folder)?;
-
-        let mut f = fs::File::create_new(folder.join(binary_path))?;
-        let metadata = fs::File::create_new(folder.join(BUILD_FILE_NAME))?;
-        serde_json::to_writer(metadata, &build)?;
+        let part = folder.join(format!("{}.part", build.name));
+        let mut f = fs::File::create(&part)?;
+        std::io::copy(binary, &mut f)?;
         f.flush()?;
         #[cfg(target_family = "unix")]
         {
             use std::{fs::Permissions, os::unix::fs::PermissionsExt};
             f.set_permissions(Permissions::from_mode(0o755))?;
         }
+        drop(f);
+
+        let actual = sha256_reader(&mut fs::File::open(&part)?)?;
+        if !actual.eq_ignore_ascii_case(&build.sha256) {
+            fs::remove_file(&part)?;
+            return Err(Error::ChecksumValidationError {
+                expected: build.sha256.clone(),
+                actual,
+            });
+        }
+        fs::rename(&part, &path)?;
 
-        f.write_all(binary_blob).map_err(Into::into)
+        let metadata = fs::File::create(folder.join(BUILD_FILE_NAME))?;
+        serde_json::to_writer(metadata, &build)?;
+        InstallMetadata::new(build).write(&folder)?;
+        Ok(())
     }
     /// Retrieve default version of Resolc for use if it's present.
     fn get_default_version(&self) -> Result<Version, Error> {
@@ -78,7 +124,8 @@ pub(crate) trait FsPaths {
     fn remove_default(&self) -> Result<(), Error> {
         let _lock_file = self.create_lock_file(&Version::new(0, 0, 0))?;
 
-        std::fs::remove_file(self.default_version_path()).map_err(Into::into)
+        std::fs::remove_file(self.default_version_path())?;
+        self.update_index()
     }
 
     /// Sets a default version of Resolc to be used globally
@@ -86,11 +133,106 @@ pub(crate) trait FsPaths {
         let _lock_file = self.create_lock_file(&Version::new(0, 0, 0))?;
 
         std::fs::File::create(self.default_version_path())?
-            .write_all(version.to_string().as_bytes())
-            .map_err(Into::into)
+            .write_all(version.to_string().as_bytes())?;
+        history::append(&self.path().join(HISTORY_FILE_NAME), version)?;
+        self.update_index()
+    }
+
+    /// Reads the log of default version changes, oldest change first
+    fn default_history(&self) -> Result<Vec<DefaultChange>, Error> {
+        history::read(&self.path().join(HISTORY_FILE_NAME)).map_err(Into::into)
+    }
+
+    /// Reads user configuration, returns the default one if it was never written.
+    fn read_config(&self) -> Result<Config, Error> {
+        match std::fs::read_to_string(self.path().join(CONFIG_FILE_NAME)) {
+            Ok(config) => serde_json::from_str(&config).map_err(Into::into),
+            Err(err) if err.kind() == ErrorKind::NotFound => Ok(Config::default()),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Stores user configuration
+    fn write_config(&self, config: &Config) -> Result<(), Error> {
+        let _lock_file = self.create_named_lock_file("config")?;
+
+        let file = std::fs::File::create(self.path().join(CONFIG_FILE_NAME))?;
+        serde_json::to_writer_pretty(file, config).map_err(Into::into)
+    }
+
+    /// Reads `build.json` metadata of the installed version
+    fn installed_build(&self, version: &Version) -> Result<Build, Error> {
+        let file = self.path().join(version.to_string()).join(BUILD_FILE_NAME);
+        match std::fs::read_to_string(file) {
+            Ok(build) => serde_json::from_str(&build).map_err(Into::into),
+            Err(err) if err.kind() == ErrorKind::NotFound => Err(Error::NotInstalled {
+                version: version.clone(),
+            }),
+            Err(err) => Err(err.into()),
+        }
+    }
+
+    /// Registers an external binary as `<Self::path>/<link name>/build.json`, replacing the link with the same name
+    fn install_link(&self, build: &Build) -> Result<(), Error> {
+        let name = &build.link.as_ref().expect("only links are registered").name;
+        let _lock_file = self.create_link_lock_file(name)?;
+
+        let folder = self.path().join(name);
+        fs::create_dir_all(&folder)?;
+        let metadata = fs::File::create(folder.join(BUILD_FILE_NAME))?;
+        serde_json::to_writer(metadata, build)?;
+        InstallMetadata::new(build).write(&folder)?;
+        self.update_index()
+    }
+
+    /// Reads `build.json` metadata of the linked binary
+    fn linked_build(&self, name: &str) -> Result<Build, Error> {
+        let unknown = || Error::UnknownLink {
+            name: name.to_owned(),
+        };
+        let file = self.path().join(name).join(BUILD_FILE_NAME);
+        let build: Build = match std::fs::read_to_string(file) {
+            Ok(build) => serde_json::from_str(&build)?,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Err(unknown()),
+            Err(err) => return Err(err.into()),
+        };
+        if build.is_link() {
+            Ok(build)
+        } else {
+            Err(unknown())
+        }
+    }
+
+    /// Removes the link from the store, the external binary is left untouched
+    fn remove_link(&self, name: &str) -> Result<(), Error> {
+        self.linked_build(name)?;
+        let _lock_file = self.create_link_lock_file(name)?;
+
+        std::fs::remove_dir_all(self.path().join(name))?;
+        self.update_index()
+    }
+
+    /// Time the version (or link) stored in `<Self::path>/<name>` was installed
+    ///
+    /// Installations predating `install.json` fall back to the time `build.json` was written.
+    fn installed_at(&self, name: &str) -> Option<SystemTime> {
+        let dir = self.path().join(name);
+        match InstallMetadata::read(&dir) {
+            Some(metadata) => Some(metadata.installed_at()),
+            None => fs::metadata(dir.join(BUILD_FILE_NAME))
+                .and_then(|metadata| metadata.modified())
+                .ok(),
+        }
+    }
+
+    /// Last time the version (or link) stored in `<Self::path>/<name>` was run by the `resolc` wrapper
+    fn last_used(&self, name: &str) -> Option<SystemTime> {
+        usage::last_used(&self.path().join(name))
     }
 
     /// Build a list of installed binaries using the `build.json` metadata that is stored alongside them.
+    ///
+    /// Linked binaries are included as well.
     fn installed_versions(&self) -> Result<Vec<Build>, Error> {
         let files = std::fs::read_dir(self.path())?
             .filter_map(|e| e.ok())
@@ -126,13 +268,56 @@ pub(crate) trait FsPaths {
             }
         }
 
-        std::fs::remove_dir_all(path).map_err(Into::into)
+        std::fs::remove_dir_all(path)?;
+        self.update_index()
+    }
+
+    /// Rebuilds the store index (see [`StoreIndex`]) and the shims in `<Self::path>/bin`
+    fn update_index(&self) -> Result<(), Error> {
+        let _lock_file = self.create_named_lock_file("index")?;
+
+        let index = StoreIndex::new(
+            self.get_default_version().ok(),
+            self.installed_versions()?,
+            &self.read_config().unwrap_or_default().aliases,
+            self.path(),
+        );
+        // Written to a temporary file first, so readers never see a partially written index.
+        let path = self.path().join(INDEX_FILE_NAME);
+        let tmp = path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_vec(&index)?)?;
+        fs::rename(tmp, path)?;
+
+        shims::update(self.path(), &index)
+    }
+
+    /// Reads the store index, `None` if it's missing or unreadable
+    fn read_index(&self) -> Option<StoreIndex> {
+        let index = fs::read(self.path().join(INDEX_FILE_NAME)).ok()?;
+        serde_json::from_slice(&index).ok()
+    }
+
+    /// Creates the store index if it's missing, e.g. in stores created by older versions
+    fn ensure_index(&self) -> Result<(), Error> {
+        if self.path().join(INDEX_FILE_NAME).exists() {
+            return Ok(());
+        }
+        self.update_index()
     }
 
     fn create_lock_file(&self, version: &Version) -> Result<LockFile, Error> {
+        self.create_named_lock_file(&version.to_string())
+    }
+
+    /// Lock of a linked binary, prefixed so link names can't take the locks of the store, e.g. `index`
+    fn create_link_lock_file(&self, name: &str) -> Result<LockFile, Error> {
+        self.create_named_lock_file(&format!("link-{name}"))
+    }
+
+    fn create_named_lock_file(&self, name: &str) -> Result<LockFile, Error> {
         use fs4::fs_std::FileExt;
 
-        let path = self.path().join(format!(".lock-{version}"));
+        let path = self.path().join(format!(".lock-{name}"));
         let _file = std::fs::File::options()
             .read(true)
             .create(true)
@@ -157,7 +342,8 @@ impl Drop for LockFile {
 
 /// Implementation used by default.
 ///
-///
+/// The store is `$RVM_HOME` if it's set, otherwise `~/.rvm`, or the `rvm` folder of the user data directory
+/// if `~/.rvm` doesn't exist.
 #[derive(Clone)]
 pub struct DataDir {
     path: PathBuf,
@@ -178,8 +364,20 @@ fn create_dir(path: &PathBuf) -> Result<(), Error> {
     Ok(())
 }
 
+impl DataDir {
+    /// Store in the given folder, it's created if it's missing
+    pub(crate) fn at(path: PathBuf) -> Result<Self, Error> {
+        create_dir(&path)?;
+        Ok(Self { path })
+    }
+}
+
 impl FsPaths for DataDir {
     fn new() -> Result<Self, Error> {
+        if let Some(path) = std::env::var_os(HOME_ENV).filter(|path| !path.is_empty()) {
+            return Self::at(PathBuf::from(path));
+        }
+
         let home_dir = dirs::home_dir()
             .ok_or(Error::IoError(std::io::Error::new(
                 std::io::ErrorKind::NotFound,
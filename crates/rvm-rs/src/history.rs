@@ -0,0 +1,127 @@
+use std::{fs, io::Write, path::Path, time::SystemTime};
+
+use semver::Version;
+use serde::{Deserialize, Serialize};
+
+/// File of the store logging every change of the default version, one JSON object per line.
+pub(crate) const HISTORY_FILE_NAME: &str = ".default_history";
+
+/// Change of the default version, see [`crate::VersionManager::default_history`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DefaultChange {
+    /// Version that became the default
+    pub version: Version,
+    /// Time the default was changed
+    #[serde(with = "unix_seconds")]
+    pub changed_at: SystemTime,
+}
+
+/// Appends the change to the log, callers hold the lock of the default version
+pub(crate) fn append(path: &Path, version: &Version) -> std::io::Result<()> {
+    let change = DefaultChange {
+        version: version.clone(),
+        changed_at: SystemTime::now(),
+    };
+    let mut line = serde_json::to_vec(&change)?;
+    line.push(b'\n');
+    fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path)?
+        .write_all(&line)
+}
+
+/// Reads the log, oldest change first. Lines that can't be parsed are skipped.
+pub(crate) fn read(path: &Path) -> std::io::Result<Vec<DefaultChange>> {
+    let log = match fs::read_to_string(path) {
+        Ok(log) => log,
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+    Ok(log
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// Most recent default other than the current one that is still installed, removed versions are skipped
+pub(crate) fn previous<'a>(
+    history: &'a [DefaultChange],
+    current: Option<&Version>,
+    is_installed: impl Fn(&Version) -> bool,
+) -> Option<&'a Version> {
+    history
+        .iter()
+        .rev()
+        .map(|change| &change.version)
+        .find(|version| Some(*version) != current && is_installed(version))
+}
+
+mod unix_seconds {
+    use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub(super) fn serialize<S: Serializer>(
+        time: &SystemTime,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(
+            time.duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+        )
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<SystemTime, D::Error> {
+        Ok(UNIX_EPOCH + Duration::from_secs(u64::deserialize(deserializer)?))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use semver::Version;
+
+    use super::{append, previous, read};
+
+    #[test]
+    fn previous_default() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join(super::HISTORY_FILE_NAME);
+        assert!(read(&path).unwrap().is_empty());
+
+        let versions: Vec<Version> = ["0.3.0", "0.4.0", "0.4.0", "0.5.0-nightly.2025.8.9"]
+            .iter()
+            .map(|version| Version::parse(version).unwrap())
+            .collect();
+        for version in &versions {
+            append(&path, version).unwrap();
+        }
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .and_then(|mut file| std::io::Write::write_all(&mut file, b"corrupted\n"))
+            .unwrap();
+
+        let history = read(&path).unwrap();
+        assert_eq!(history.len(), 4);
+        let all = |_: &Version| true;
+        assert_eq!(
+            previous(&history, Some(&versions[3]), all),
+            Some(&versions[1])
+        );
+        assert_eq!(
+            previous(&history, Some(&versions[1]), all),
+            Some(&versions[3])
+        );
+        assert_eq!(previous(&history, None, all), Some(&versions[3]));
+        assert_eq!(previous(&history[..1], Some(&versions[0]), all), None);
+        let removed = |version: &Version| version != &versions[1];
+        assert_eq!(
+            previous(&history, Some(&versions[3]), removed),
+            Some(&versions[0])
+        );
+    }
+}
@@ -0,0 +1,133 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use semver::Version;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    releases::{Binary, Build},
+    spec::VersionSpec,
+};
+
+/// Summary of the store, stored as `.index.json` in the installation folder.
+///
+/// It lets the `resolc` wrapper find a binary with a single small read instead of parsing every `build.json`,
+/// and is rebuilt whenever the store changes.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct StoreIndex {
+    /// Default version
+    #[serde(default)]
+    pub(crate) default: Option<Version>,
+    /// Paths to the installed binaries by version
+    #[serde(default)]
+    pub(crate) versions: BTreeMap<Version, PathBuf>,
+    /// Paths to the linked binaries by name
+    #[serde(default)]
+    pub(crate) links: BTreeMap<String, PathBuf>,
+    /// Paths to the installed binaries selected by aliases, aliases selecting no installed version are left out
+    #[serde(default)]
+    pub(crate) aliases: BTreeMap<String, PathBuf>,
+}
+
+impl StoreIndex {
+    pub(crate) fn new(
+        default: Option<Version>,
+        builds: Vec<Build>,
+        aliases: &BTreeMap<String, String>,
+        store: &Path,
+    ) -> Self {
+        let mut index = Self {
+            default,
+            ..Default::default()
+        };
+        for build in builds {
+            let link = build.link.as_ref().map(|link| link.name.clone());
+            let Binary::Local { path, info } = build.into_local(store) else {
+                continue;
+            };
+            match link {
+                Some(name) => index.links.insert(name, path),
+                None => index.versions.insert(info.version, path),
+            };
+        }
+        for (name, target) in aliases {
+            let Ok(spec) = target.parse::<VersionSpec>() else {
+                continue;
+            };
+            if let Some(version) = spec.select(index.versions.keys()) {
+                let path = index.versions[version].clone();
+                index.aliases.insert(name.clone(), path);
+            }
+        }
+        index
+    }
+
+    /// Path to the binary of an exact version, a link name or an alias
+    pub(crate) fn binary(&self, selector: &str) -> Option<&Path> {
+        match selector.parse::<Version>() {
+            Ok(version) => self.versions.get(&version),
+            Err(_) => self
+                .links
+                .get(selector)
+                .or_else(|| self.aliases.get(selector)),
+        }
+        .map(PathBuf::as_path)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use semver::Version;
+
+    use crate::{fs::FsPaths, releases::Build, test::TempDir};
+
+    fn build(version: &str) -> Build {
+        serde_json::from_value(serde_json::json!({
+            "name": "resolc",
+            "version": version,
+            "longVersion": version,
+            "url": "https://example.com/resolc",
+            "sha256": crate::releases::sha256(version.as_bytes()),
+            "firstSolcVersion": "0.8.0",
+            "lastSolcVersion": "0.8.30"
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn updated_with_the_store() {
+        let store = TempDir::new().unwrap();
+        assert_eq!(store.read_index(), None);
+
+        let version = Version::new(0, 4, 0);
+        store
+            .install_version(&build("0.4.0"), "0.4.0".as_bytes())
+            .unwrap();
+        store.set_default_version(&version).unwrap();
+        let index = store.read_index().unwrap();
+        assert_eq!(index.default, Some(version.clone()));
+        let path = store.path().join("0.4.0").join("resolc");
+        assert_eq!(index.binary("0.4.0"), Some(path.as_path()));
+        assert_eq!(index.binary("dev"), None);
+
+        let mut link = build("0.5.0-dev.1");
+        link.link = Some(crate::local::Link {
+            name: "dev".to_owned(),
+            path: "/opt/resolc".into(),
+        });
+        store.install_link(&link).unwrap();
+        assert_eq!(
+            store.read_index().unwrap().binary("dev"),
+            Some(Path::new("/opt/resolc"))
+        );
+
+        store.remove_version(&version).unwrap();
+        let index = store.read_index().unwrap();
+        assert_eq!(index.default, None);
+        assert!(index.versions.is_empty());
+    }
+}
@@ -0,0 +1,95 @@
+use std::{path::PathBuf, time::SystemTime};
+
+use semver::Version;
+use serde::Serialize;
+use url::Url;
+
+use crate::releases::Build;
+
+/// Full metadata of a Resolc version, combines the release with the local installation.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct VersionInfo {
+    /// Resolc version
+    pub version: Version,
+    /// Full version including build metadata, e.g. `0.1.0-dev.13+commit.ad331534`
+    pub long_version: String,
+    /// Commit the binary is built from, if it's part of the long version
+    pub commit: Option<String>,
+    /// File name of the binary
+    pub name: String,
+    /// URL the binary is downloaded from
+    pub url: Url,
+    /// sha256 of the binary
+    pub sha256: String,
+    /// first supported `solc` version
+    pub first_supported_solc_version: Version,
+    /// last supported `solc` version
+    pub last_supported_solc_version: Version,
+    /// Name of the registry the binary comes from
+    pub registry: Option<String>,
+    /// Local installation, `None` if the version isn't installed
+    pub installation: Option<Installation>,
+    /// Whether the version is the default one
+    pub is_default: bool,
+}
+
+/// Installed binary of a Resolc version.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Installation {
+    /// Path to the installed binary
+    pub path: PathBuf,
+    /// Time the version was installed
+    pub installed_at: Option<SystemTime>,
+    /// Last time the version was run by the `resolc` wrapper
+    pub last_used: Option<SystemTime>,
+    /// URL the binary was installed from, `None` for installations predating the install metadata
+    pub source: Option<Url>,
+    /// Size of the binary in bytes
+    pub size: u64,
+    /// Whether the binary matches the sha256 of its build
+    pub verified: bool,
+}
+
+impl VersionInfo {
+    pub(crate) fn new(build: Build, installation: Option<Installation>, is_default: bool) -> Self {
+        Self {
+            commit: commit(&build.long_version),
+            version: build.version,
+            long_version: build.long_version,
+            name: build.name,
+            url: build.url,
+            sha256: build.sha256,
+            first_supported_solc_version: build.first_supported_solc_version,
+            last_supported_solc_version: build.last_supported_solc_version,
+            registry: build.registry,
+            installation,
+            is_default,
+        }
+    }
+}
+
+/// Extracts the commit from `<version>+commit.<hash>[.<more metadata>]`
+fn commit(long_version: &str) -> Option<String> {
+    let (_, metadata) = long_version.split_once('+')?;
+    let mut parts = metadata.split('.');
+    parts.find(|part| *part == "commit")?;
+    parts.next().map(ToOwned::to_owned)
+}
+
+#[cfg(test)]
+mod test {
+    use super::commit;
+
+    #[test]
+    fn commit_from_long_version() {
+        assert_eq!(
+            commit("0.1.0-dev.13+commit.ad331534").as_deref(),
+            Some("ad331534")
+        );
+        assert_eq!(
+            commit("0.1.0-dev.13+commit.ad33153.llvm-18.1.8").as_deref(),
+            Some("ad33153")
+        );
+        assert_eq!(commit("0.4.0"), None);
+    }
+}
@@ -6,18 +6,65 @@
     warn(unused_crate_dependencies)
 )]
 
-use constants::Platform;
 use fs::FsPaths;
+use local::{Link, Probe};
+use oci::OciRepository;
 use semver::Version;
-use std::collections::{BTreeMap, BTreeSet};
+use std::{
+    collections::BTreeSet,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
 
+#[cfg(feature = "bundle")]
+mod bundle;
+mod config;
 mod constants;
 mod errors;
 mod fs;
+mod history;
+mod index;
+mod info;
+mod local;
+mod lockfile;
+mod mirror;
+mod oci;
+mod pragma;
+mod prune;
+mod registry;
 mod releases;
+mod resolve;
+mod served;
+mod shims;
+mod spec;
+mod tools;
+mod upgrade;
+mod usage;
+#[cfg(feature = "bundle")]
+pub use bundle::{Bundle, BundleEntry, BundleManifest, ImportReport};
+pub use config::{Alias, Config, AUTO_INSTALL_ENV};
+pub use constants::{Platform, REPO_URL};
 pub use errors::Error;
+pub use fs::HOME_ENV;
+pub use history::DefaultChange;
+pub use info::{Installation, VersionInfo};
+pub use local::ScanReport;
+pub use lockfile::{LockedBinary, Lockfile, LOCK_FILE_NAME};
+pub use mirror::{Mirror, MirrorOptions, MirrorReport};
+pub use oci::{OCI_PASSWORD_ENV, OCI_USERNAME_ENV};
+pub use pragma::{Pragma, SourcesResolution, RESOLVE_SOURCES_ENV};
+pub use prune::PrunePolicy;
+pub use registry::{
+    Registry, RegistryVersion, SkippedBuild, UnavailableRegistry, OFFICIAL_REGISTRY,
+};
+use releases::{file_path, Build, Releases};
 pub use releases::{Binary, BinaryInfo};
-use releases::{Build, Releases};
+pub use resolve::{Candidate, Resolution, Source, PATH_ENV, PROJECT_FILE_NAME, VERSION_ENV};
+pub use served::ServedReleases;
+pub use shims::shim_selector;
+pub use spec::{Channel, VersionSpec};
+pub use tools::{ToolVersion, PROJECT_CONFIGS_ENV};
+pub use upgrade::{OutdatedVersion, Upgrade, UpgradeTarget};
 
 /// Version manager responsible for handling Resolc installation.
 pub struct VersionManager {
@@ -34,10 +81,34 @@ impl VersionManager {
     /// * `offline` - run in offline mode.
     pub fn new(offline: bool) -> Result<Self, Error> {
         let fspaths = fs::DataDir::new()?;
+        fspaths.ensure_index()?;
         let releases = if offline {
             Self::get_releases_offline(&fspaths)?
         } else {
-            Self::get_releases()?
+            Self::get_releases(&fspaths.read_config()?)?
+        };
+        Ok(Self {
+            offline,
+            fs: Box::new(fspaths),
+            releases,
+        })
+    }
+
+    /// Instantiate the version manager with the store in the given folder instead of the default one,
+    /// e.g. a temporary store
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - folder of the store, it's created if it's missing
+    /// * `offline` - run in offline mode.
+    /// * `config` - configuration providing the registries, the one in `path` isn't read
+    pub fn new_in(path: &Path, offline: bool, config: &Config) -> Result<Self, Error> {
+        let fspaths = fs::DataDir::at(path.to_path_buf())?;
+        fspaths.ensure_index()?;
+        let releases = if offline {
+            Self::get_releases_offline(&fspaths)?
+        } else {
+            Self::get_releases(config)?
         };
         Ok(Self {
             offline,
@@ -50,7 +121,7 @@ impl VersionManager {
     /// For use in tests
     pub fn new_in_temp() -> Self {
         use test::TempDir;
-        let releases = Self::get_releases().expect("no network");
+        let releases = Self::get_releases(&Config::default()).expect("no network");
 
         VersionManager {
             offline: false,
@@ -59,41 +130,75 @@ impl VersionManager {
         }
     }
 
-    fn get_releases() -> Result<Releases, Error> {
+    /// Fetches the official releases and releases of every configured registry,
+    /// merged in the order of registry priority.
+    fn get_releases(config: &Config) -> Result<Releases, Error> {
         let url = Platform::get()?.download_url(false)?;
         let nightly_url = Platform::get()?.download_url(true)?;
-        Releases::new(url)
+        let official = Releases::new(url)
             .and_then(|releases| Releases::new(nightly_url).map(|nightlies| (releases, nightlies)))
             .map(|(mut releases, mut nightlies)| {
                 releases.merge(&mut nightlies);
-                releases
-            })
+                releases.with_registry(OFFICIAL_REGISTRY)
+            })?;
+        Ok(Self::with_registries(official, &config.registries))
+    }
+
+    /// Merges the releases of the registries into the official ones by priority.
+    ///
+    /// Registries that can't be fetched are skipped and reported in [`VersionManager::unavailable_registries`],
+    /// so one unreachable registry doesn't break every command.
+    fn with_registries(official: Releases, registries: &[Registry]) -> Releases {
+        let latest_release = official.latest_release.clone();
+
+        // Official releases come first, so they win over registries with the same priority.
+        let mut all = vec![(0, official)];
+        let mut unavailable = Vec::new();
+        for registry in registries {
+            match Releases::new(registry.url.clone()) {
+                Ok(releases) => {
+                    all.push((registry.priority, releases.with_registry(&registry.name)))
+                }
+                Err(err) => unavailable.push(UnavailableRegistry {
+                    name: registry.name.clone(),
+                    reason: err.to_string(),
+                }),
+            }
+        }
+        all.sort_by_key(|(priority, _)| std::cmp::Reverse(*priority));
+
+        let mut all = all.into_iter().map(|(_, releases)| releases);
+        let mut releases = all.next().expect("official releases are always present");
+        for mut other in all {
+            releases.merge(&mut other);
+        }
+        releases.latest_release = latest_release;
+        releases.unavailable = unavailable;
+
+        releases
+    }
+
+    /// Registries whose releases couldn't be fetched, their versions are missing from the release list
+    pub fn unavailable_registries(&self) -> &[UnavailableRegistry] {
+        &self.releases.unavailable
+    }
+
+    /// Builds of registries that were left out of the release list because their artifacts are invalid
+    pub fn skipped_builds(&self) -> &[SkippedBuild] {
+        &self.releases.skipped
     }
 
     fn get_releases_offline(data: &impl FsPaths) -> Result<Releases, Error> {
-        let installed = data.installed_versions()?;
+        let installed: Vec<Build> = data
+            .installed_versions()?
+            .into_iter()
+            .filter(|build| !build.is_link())
+            .collect();
         if installed.is_empty() {
             return Err(Error::NoVersionsInstalled);
         }
-        let releases = BTreeMap::from_iter(installed.iter().map(|data| {
-            (
-                data.version.clone(),
-                format!("{}+{}", data.name, data.long_version),
-            )
-        }));
-
-        let latest_release = installed
-            .iter()
-            .max_by(|a, b| a.version.cmp(&b.version))
-            .map(|x| &x.version)
-            .cloned()
-            .expect("Cant be empty");
 
-        Ok(Releases {
-            builds: installed,
-            releases,
-            latest_release,
-        })
+        Ok(Releases::from_builds(installed))
     }
 
     /// checks whether the requested resolc binary version is installed already
@@ -116,8 +221,11 @@ impl VersionManager {
         resolc_version: &Version,
         solc_version: Option<Version>,
     ) -> Result<Binary, Error> {
-        let releases = &self.releases;
-        let build = releases.get_build(resolc_version)?;
+        // The installed metadata names the build in the store, the releases may prefer another registry's build.
+        let build = match self.fs.installed_build(resolc_version) {
+            Ok(build) => build,
+            Err(_) => self.releases.get_build(resolc_version)?.clone(),
+        };
 
         if let Some(solc_version) = solc_version {
             build.check_solc_compat(&solc_version)?;
@@ -131,7 +239,7 @@ impl VersionManager {
             .join(&build.name)
             .exists()
         {
-            Ok(build.clone().into_local(self.fs.path()))
+            Ok(build.into_local(self.fs.path()))
         } else {
             Err(Error::NotInstalled {
                 version: resolc_version.clone(),
@@ -150,6 +258,37 @@ impl VersionManager {
         resolc_version: &Version,
         solc_version: Option<Version>,
     ) -> Result<Binary, Error> {
+        self.get_or_install_from(None, resolc_version, solc_version)
+    }
+
+    /// Returns an already present binary or installs the requested Resolc version from the given registry
+    ///
+    /// # Arguments
+    ///
+    /// * `registry` - name of the registry to install from, the registry with the highest priority providing the version is used when not set
+    /// * `resolc_version` - required Resolc version
+    /// * `solc_version` - optional `solc` version requirement, passing this will also check the compatibility between the two compiler versions
+    pub fn get_or_install_from(
+        &self,
+        registry: Option<&str>,
+        resolc_version: &Version,
+        solc_version: Option<Version>,
+    ) -> Result<Binary, Error> {
+        // The store keeps one build per version, a build of another registry isn't silently returned instead.
+        if let Some(registry) = registry {
+            if let Ok(installed) = self.fs.installed_build(resolc_version) {
+                let installed = installed
+                    .registry
+                    .unwrap_or_else(|| OFFICIAL_REGISTRY.to_owned());
+                if installed != registry {
+                    return Err(Error::InstalledFromOtherRegistry {
+                        version: resolc_version.clone(),
+                        installed,
+                        requested: registry.to_owned(),
+                    });
+                }
+            }
+        }
         match self.get(resolc_version, solc_version) {
             bin @ Ok(_) => {
                 return bin;
@@ -161,15 +300,72 @@ impl VersionManager {
         if self.offline {
             return Err(Error::CantInstallOffline);
         }
-        let build = self.releases.get_build(resolc_version)?;
+        let build = self.releases.get_build_in(registry, resolc_version)?;
 
-        let binary = build.download_binary()?;
+        let binary = self.download(build)?;
 
         self.fs.install_version(build, &binary)?;
 
         Ok(build.clone().into_local(self.fs.path()))
     }
 
+    /// Downloads the binary of the build, builds of OCI registries are downloaded with the registry credentials
+    fn download(&self, build: &Build) -> Result<Vec<u8>, Error> {
+        let Some(name) = build
+            .registry
+            .as_deref()
+            .filter(|name| *name != OFFICIAL_REGISTRY)
+        else {
+            return build.download_binary();
+        };
+        let config = self.fs.read_config()?;
+        match config
+            .registries
+            .iter()
+            .find(|registry| registry.name == name && OciRepository::is_oci(&registry.url))
+        {
+            Some(registry) => {
+                OciRepository::from_url(&registry.url, &Platform::get()?)?.download(build)
+            }
+            None => build.download_binary(),
+        }
+    }
+
+    /// Returns an already present binary or installs the newest release matching a version or a version specification
+    pub fn get_or_install_matching(&self, selector: &str) -> Result<Binary, Error> {
+        let spec = match self.fs.read_config()?.alias(selector) {
+            Some(spec) => spec,
+            None => selector.parse::<VersionSpec>()?,
+        };
+        let version = spec
+            .select(self.releases.builds.iter().map(|build| &build.version))
+            .cloned()
+            .ok_or_else(|| Error::NoMatchingVersion {
+                spec: selector.to_owned(),
+            })?;
+        self.get_or_install(&version, None)
+    }
+
+    /// Folder with the `resolc` and `resolc-<version>` shims, meant to be added to `PATH`
+    pub fn shim_dir(&self) -> PathBuf {
+        self.fs.path().join(shims::SHIM_DIR)
+    }
+
+    /// Folder with the shims of the default store, see [`VersionManager::shim_dir`]
+    pub fn default_shim_dir() -> Result<PathBuf, Error> {
+        Ok(fs::DataDir::new()?.path().join(shims::SHIM_DIR))
+    }
+
+    /// Regenerates the shims and the store index, e.g. after the `resolc` wrapper was reinstalled
+    pub fn update_shims(&self) -> Result<(), Error> {
+        self.fs.update_index()
+    }
+
+    /// Returns the user configuration stored in the installation folder
+    pub fn config(&self) -> Result<Config, Error> {
+        self.fs.read_config()
+    }
+
     /// Uninstall the listed version if it exists in path
     pub fn remove(&self, version: &Version) -> Result<(), Error> {
         if !self
@@ -187,6 +383,621 @@ impl VersionManager {
         self.fs.remove_version(version)
     }
 
+    /// Removes the installed versions selected by the policy, returns them in ascending order.
+    ///
+    /// The default version and the version pinned by the project of `dir` are kept, linked binaries aren't touched.
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - rules selecting the versions to remove
+    /// * `dir` - directory `.resolc-version` project files are looked up from
+    /// * `dry_run` - only return the versions that would be removed
+    pub fn prune(
+        &self,
+        policy: &PrunePolicy,
+        dir: &Path,
+        dry_run: bool,
+    ) -> Result<Vec<Version>, Error> {
+        let builds: Vec<Build> = self
+            .fs
+            .installed_versions()?
+            .into_iter()
+            .filter(|build| !build.is_link())
+            .collect();
+        let stable: BTreeSet<Version> = builds
+            .iter()
+            .chain(&self.releases.builds)
+            .map(|build| &build.version)
+            .filter(|version| Channel::of(version) == Channel::Stable)
+            .cloned()
+            .collect();
+
+        let mut protected = BTreeSet::new();
+        if let Ok(default) = self.fs.get_default_version() {
+            protected.insert(default);
+        }
+        if let Some(version) = Self::project_version(dir)?.and_then(|project| self.pinned(&project))
+        {
+            protected.insert(version);
+        }
+
+        let installed: Vec<prune::Installed> = builds
+            .iter()
+            .map(|build| prune::Installed {
+                version: build.version.clone(),
+                last_used: {
+                    let name = build.version.to_string();
+                    self.fs.last_used(&name).or(self.fs.installed_at(&name))
+                },
+            })
+            .collect();
+        let selected = prune::select(policy, &installed, &stable, &protected, SystemTime::now());
+        if !dry_run {
+            for version in &selected {
+                self.fs.remove_version(version)?;
+            }
+        }
+        Ok(selected)
+    }
+
+    /// Version requested by the candidate: its exact version, or the installed version selected by its specification
+    fn pinned(&self, candidate: &Candidate) -> Option<Version> {
+        match candidate.selector.parse::<Version>() {
+            Ok(version) => Some(version),
+            Err(_) => self
+                .select(&candidate.selector)
+                .ok()
+                .map(|binary| binary.version().clone()),
+        }
+    }
+
+    /// Lists the installed versions, the default version and the version pinned by the project of `dir`
+    /// with the newest releases of their channels
+    pub fn outdated(&self, dir: &Path) -> Result<Vec<OutdatedVersion>, Error> {
+        let installed: BTreeSet<Version> = self
+            .fs
+            .installed_versions()?
+            .into_iter()
+            .filter(|build| !build.is_link())
+            .map(|build| build.version)
+            .collect();
+        let default = self.fs.get_default_version().ok();
+        let project = Self::project_version(dir)?.and_then(|project| {
+            let version = self.pinned(&project)?;
+            match project.source {
+                Source::ProjectFile { path } | Source::LockFile { path } => Some((version, path)),
+                _ => None,
+            }
+        });
+        let releases: BTreeSet<&Version> = self
+            .releases
+            .builds
+            .iter()
+            .map(|build| &build.version)
+            .chain(&installed)
+            .collect();
+
+        let versions: BTreeSet<&Version> = installed
+            .iter()
+            .chain(&default)
+            .chain(project.as_ref().map(|(version, _)| version))
+            .collect();
+        Ok(versions
+            .into_iter()
+            .map(|version| OutdatedVersion {
+                version: version.clone(),
+                is_default: default.as_ref() == Some(version),
+                project: project
+                    .as_ref()
+                    .filter(|(pinned, _)| pinned == version)
+                    .map(|(_, path)| path.clone()),
+                installed: installed.contains(version),
+                compatible: upgrade::newer(version, releases.iter().copied(), true).cloned(),
+                latest: upgrade::newer(version, releases.iter().copied(), false).cloned(),
+            })
+            .collect())
+    }
+
+    /// Installs the newest release of the channel and switches the default or the project of `dir` to it
+    ///
+    /// # Arguments
+    ///
+    /// * `channel` - channel to upgrade to, the channel of the current version by default
+    /// * `target` - whether the default version or the project file is switched
+    /// * `dir` - directory `.resolc-version` project files are looked up from
+    /// * `prune` - remove the previous version, unless it's still the default or pinned by the project
+    pub fn upgrade(
+        &self,
+        channel: Option<Channel>,
+        target: UpgradeTarget,
+        dir: &Path,
+        prune: bool,
+    ) -> Result<Upgrade, Error> {
+        let default = self.fs.get_default_version().ok();
+        let project = Self::project_version(dir)?;
+        let pinned = project.as_ref().and_then(|project| self.pinned(project));
+        let from = match target {
+            UpgradeTarget::Default => default.clone(),
+            UpgradeTarget::Project if project.is_none() => return Err(Error::NoProjectFile),
+            UpgradeTarget::Project => pinned.clone(),
+        };
+        let channel = channel
+            .or(from.as_ref().map(Channel::of))
+            .unwrap_or(Channel::Stable);
+        let latest = self
+            .releases
+            .builds
+            .iter()
+            .map(|build| &build.version)
+            .filter(|version| Channel::of(version) == channel)
+            .max()
+            .cloned()
+            .ok_or(Error::NoReleasesInChannel { channel })?;
+
+        let to = self.get_or_install(&latest, None)?;
+        let project = match target {
+            UpgradeTarget::Default => {
+                self.fs.set_default_version(&latest)?;
+                None
+            }
+            UpgradeTarget::Project => {
+                let path = match project.map(|project| project.source) {
+                    Some(Source::ProjectFile { path }) => path,
+                    Some(Source::LockFile { path }) => return Err(Error::ProjectLocked { path }),
+                    _ => return Err(Error::NoProjectFile),
+                };
+                std::fs::write(&path, format!("{latest}\n"))?;
+                Some(path)
+            }
+        };
+
+        let still_used = |version: &Version| match target {
+            UpgradeTarget::Default => pinned.as_ref() == Some(version),
+            UpgradeTarget::Project => default.as_ref() == Some(version),
+        };
+        let pruned = match &from {
+            Some(from)
+                if prune && *from != latest && self.is_installed(from) && !still_used(from) =>
+            {
+                self.fs.remove_version(from)?;
+                true
+            }
+            _ => false,
+        };
+        Ok(Upgrade {
+            from,
+            to,
+            project,
+            pruned,
+        })
+    }
+
+    /// Locks the exact build of the version for the project of `dir`, returns the path to the written `rvm.lock`.
+    ///
+    /// The lockfile is written next to the closest project file, or into `dir` if there's none.
+    ///
+    /// # Arguments
+    ///
+    /// * `selector` - version, version specification or alias to lock, the version `resolc` runs in `dir` by default
+    /// * `dir` - directory of the project
+    /// * `platforms` - platforms to lock, the binary of the current platform is locked anyway. Every platform
+    ///   the version is released for is locked when empty, only the current one in offline mode.
+    pub fn lock(
+        &self,
+        selector: Option<&str>,
+        dir: &Path,
+        platforms: &[Platform],
+    ) -> Result<(PathBuf, Lockfile), Error> {
+        let binary = match selector {
+            Some(selector) => self.select(selector)?,
+            None => self.resolve(None, dir)?.binary,
+        };
+        if let Some(name) = &binary.info().link {
+            return Err(Error::CantLockLink { name: name.clone() });
+        }
+        let build = self.fs.installed_build(binary.version())?;
+        let current = Platform::get()?;
+        let mut lockfile = Lockfile::new(&build);
+        lockfile.insert(current, &build);
+
+        let nightly = Channel::of(&build.version) == Channel::Nightly;
+        let other_platforms = if !platforms.is_empty() || self.offline {
+            platforms.to_vec()
+        } else {
+            Platform::ALL.to_vec()
+        };
+        for platform in other_platforms
+            .into_iter()
+            .filter(|platform| *platform != current)
+        {
+            if self.offline {
+                return Err(Error::CantInstallOffline);
+            }
+            let releases =
+                match self.platform_releases(build.registry.as_deref(), platform, nightly) {
+                    Ok(releases) => releases,
+                    Err(err @ Error::NoPlatformReleases { .. }) if !platforms.is_empty() => {
+                        return Err(err)
+                    }
+                    Err(Error::NoPlatformReleases { .. }) => continue,
+                    Err(err) => return Err(err),
+                };
+            match releases.get_build(&build.version) {
+                Ok(other) => lockfile.insert(platform, other),
+                // Platforms the version isn't released for are only an error when they are asked for.
+                Err(err) if !platforms.is_empty() => return Err(err),
+                Err(_) => {}
+            }
+        }
+
+        let path = match Self::project_version(dir)? {
+            Some(Candidate {
+                source: Source::ProjectFile { path } | Source::LockFile { path },
+                ..
+            }) => path.with_file_name(LOCK_FILE_NAME),
+            _ => dir.join(LOCK_FILE_NAME),
+        };
+        lockfile.write(&path)?;
+        Ok((path, lockfile))
+    }
+
+    /// Releases of another platform from the registry the build was installed from.
+    ///
+    /// Registries with a single `list.json` only serve one platform, OCI registries and release repositories
+    /// (URLs ending with `/`) have a release list per platform.
+    fn platform_releases(
+        &self,
+        registry: Option<&str>,
+        platform: Platform,
+        nightly: bool,
+    ) -> Result<Releases, Error> {
+        let name = match registry {
+            None | Some(OFFICIAL_REGISTRY) => {
+                return Ok(Releases::new(platform.download_url(nightly)?)?
+                    .with_registry(OFFICIAL_REGISTRY))
+            }
+            Some(name) => name,
+        };
+        let config = self.fs.read_config()?;
+        let registry = config
+            .registries
+            .iter()
+            .find(|registry| registry.name == name)
+            .ok_or_else(|| Error::UnknownRegistry {
+                name: name.to_owned(),
+            })?;
+        let releases = if OciRepository::is_oci(&registry.url) {
+            OciRepository::from_url(&registry.url, &platform)?.releases()?
+        } else if registry.url.path().ends_with('/') {
+            Releases::new(platform.list_url(&registry.url, nightly)?)?
+        } else {
+            return Err(Error::NoPlatformReleases {
+                registry: name.to_owned(),
+                platform,
+            });
+        };
+        Ok(releases.with_registry(name))
+    }
+
+    /// Installs the binary locked by the closest `rvm.lock` of `dir` for the current platform.
+    ///
+    /// The binary is downloaded from the locked URL and has to match the locked sha256, release lists aren't used.
+    /// Installed versions are verified against the lockfile as well.
+    pub fn sync(&self, dir: &Path) -> Result<Binary, Error> {
+        let path = lockfile::find(dir).ok_or(Error::NoLockfile)?;
+        let build = Lockfile::read(&path)?.build(Platform::get()?)?;
+
+        if let Ok(installed) = self.fs.installed_build(&build.version) {
+            let file = self
+                .fs
+                .path()
+                .join(build.version.to_string())
+                .join(&installed.name);
+            let actual = releases::sha256(&std::fs::read(file)?);
+            if !actual.eq_ignore_ascii_case(&build.sha256) {
+                return Err(Error::LockfileMismatch {
+                    version: build.version,
+                    expected: build.sha256,
+                    actual,
+                });
+            }
+            return Ok(installed.into_local(self.fs.path()));
+        }
+
+        if self.offline {
+            return Err(Error::CantInstallOffline);
+        }
+        let binary = self.download(&build)?;
+        self.fs.install_version(&build, &binary)?;
+        Ok(build.into_local(self.fs.path()))
+    }
+
+    /// Installs the Resolc versions requested by every profile of `foundry.toml`, or by a hardhat configuration.
+    ///
+    /// Versions are resolved like `rvm install` does, paths to binaries are skipped.
+    pub fn sync_from(&self, path: &Path) -> Result<Vec<Binary>, Error> {
+        let selectors: BTreeSet<String> = tools::read(path)?
+            .into_iter()
+            .filter_map(|version| version.resolc)
+            .filter(|selector| !tools::is_path(selector))
+            .collect();
+        let mut binaries: Vec<Binary> = Vec::new();
+        for selector in selectors {
+            let binary = self.get_or_install_matching(&selector)?;
+            if !binaries
+                .iter()
+                .any(|installed| installed.version() == binary.version())
+            {
+                binaries.push(binary);
+            }
+        }
+        Ok(binaries)
+    }
+
+    /// Picks the newest Resolc and `solc` pair that compiles the Solidity sources, by their `pragma solidity` constraints.
+    ///
+    /// The `solc` versions satisfying every constraint are matched against the supported range of every release.
+    ///
+    /// # Arguments
+    ///
+    /// * `paths` - `.sol` files and directories to search recursively
+    /// * `channel` - channel of the Resolc releases, stable by default
+    /// * `install` - install the Resolc version if it's missing
+    pub fn resolve_sources(
+        &self,
+        paths: &[PathBuf],
+        channel: Option<Channel>,
+        install: bool,
+    ) -> Result<SourcesResolution, Error> {
+        let pragmas = pragma::scan(paths)?;
+        let constraints = pragma::constraints(&pragmas)?;
+        let channel = channel.unwrap_or(Channel::Stable);
+        let mut builds: Vec<&Build> = self
+            .releases
+            .builds
+            .iter()
+            .filter(|build| Channel::of(&build.version) == channel)
+            .collect();
+        builds.sort_by(|a, b| b.version.cmp(&a.version));
+        let (build, solc) = builds
+            .into_iter()
+            .find_map(|build| Some((build, pragma::newest_solc(build, &constraints)?)))
+            .ok_or(Error::NoCompatibleCompiler)?;
+
+        let installed = if install {
+            self.get_or_install(&build.version, Some(solc.clone()))?;
+            true
+        } else {
+            self.fs.installed_build(&build.version).is_ok()
+        };
+        Ok(SourcesResolution {
+            pragmas,
+            resolc: build.version.clone(),
+            solc,
+            installed,
+        })
+    }
+
+    /// Lists the compiler versions requested by every profile of `foundry.toml`, or by a hardhat configuration
+    pub fn tool_versions(path: &Path) -> Result<Vec<ToolVersion>, Error> {
+        tools::read(path)
+    }
+
+    /// Registers an external Resolc binary under `name`, so it can be used as `resolc +<name>`.
+    ///
+    /// The version is read with `<path> --version`, the binary isn't verified nor copied into the store.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - name to register the binary under, can't be a version
+    /// * `path` - path to the binary
+    pub fn link(&self, name: &str, path: &Path) -> Result<Binary, Error> {
+        local::validate_link_name(name)?;
+        if self.fs.read_config()?.aliases.contains_key(name) {
+            return Err(Error::NameTaken {
+                name: name.to_owned(),
+            });
+        }
+        let mut build = self.external_build(path)?;
+        build.link = Some(Link {
+            name: name.to_owned(),
+            path: file_path(&build.url)?,
+        });
+        self.fs.install_link(&build)?;
+
+        Ok(build.into_local(self.fs.path()))
+    }
+
+    /// Describes an external binary, the version is reported by the binary itself
+    fn external_build(&self, path: &Path) -> Result<Build, Error> {
+        let path = std::fs::canonicalize(path)?;
+        let Probe {
+            version,
+            long_version,
+        } = local::probe(&path)?;
+        let binary = std::fs::read(&path)?;
+
+        // Binaries don't report supported `solc` versions, so they are taken from the release of the same version,
+        // or the widest known range.
+        let (first_supported_solc_version, last_supported_solc_version) =
+            match self.releases.get_build(&version) {
+                Ok(build) => (
+                    build.first_supported_solc_version.clone(),
+                    build.last_supported_solc_version.clone(),
+                ),
+                Err(_) => (
+                    constants::MIN_VERSION,
+                    self.releases
+                        .builds
+                        .iter()
+                        .map(|build| &build.last_supported_solc_version)
+                        .max()
+                        .cloned()
+                        .unwrap_or(constants::MIN_VERSION),
+                ),
+            };
+
+        Ok(Build {
+            name: path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default(),
+            version,
+            long_version,
+            url: url::Url::from_file_path(&path)
+                .map_err(|_| Error::InvalidPath { path: path.clone() })?,
+            first_supported_solc_version,
+            last_supported_solc_version,
+            sha256: releases::sha256(&binary),
+            registry: None,
+            link: None,
+        })
+    }
+
+    /// Removes the linked binary from the store, the binary itself isn't deleted
+    pub fn unlink(&self, name: &str) -> Result<(), Error> {
+        self.fs.remove_link(name)
+    }
+
+    /// Returns the binary linked under `name`
+    pub fn get_linked(&self, name: &str) -> Result<Binary, Error> {
+        Ok(self.fs.linked_build(name)?.into_local(self.fs.path()))
+    }
+
+    /// Sets the alias to a version specification, e.g. `prod` to `0.3.0` or `edge` to `latest-nightly`
+    ///
+    /// Aliases are stored in the configuration of the store and can be used wherever a version is accepted.
+    /// Linked binaries win over aliases, so names of links can't be taken.
+    pub fn set_alias(&self, name: &str, target: &str) -> Result<Alias, Error> {
+        let spec = target.parse::<VersionSpec>()?;
+        if self.fs.linked_build(name).is_ok() {
+            return Err(Error::NameTaken {
+                name: name.to_owned(),
+            });
+        }
+        let mut config = self.fs.read_config()?;
+        config.set_alias(name, &spec)?;
+        self.fs.write_config(&config)?;
+        self.fs.update_index()?;
+        Ok(self.alias(name.to_owned(), spec.to_string()))
+    }
+
+    /// Removes the alias, returns its target
+    pub fn remove_alias(&self, name: &str) -> Result<String, Error> {
+        let mut config = self.fs.read_config()?;
+        let target = config.remove_alias(name)?;
+        self.fs.write_config(&config)?;
+        self.fs.update_index()?;
+        Ok(target)
+    }
+
+    /// Lists aliases with the installed versions they select
+    pub fn aliases(&self) -> Result<Vec<Alias>, Error> {
+        Ok(self
+            .fs
+            .read_config()?
+            .aliases
+            .into_iter()
+            .map(|(name, target)| self.alias(name, target))
+            .collect())
+    }
+
+    fn alias(&self, name: String, target: String) -> Alias {
+        let version = self
+            .select(&target)
+            .ok()
+            .map(|binary| binary.version().clone());
+        Alias {
+            name,
+            target,
+            version,
+        }
+    }
+
+    /// Finds Resolc binaries on the system and installs the ones matching a known release.
+    ///
+    /// Binaries are identified with `--version` and matched against releases by their sha256,
+    /// so imported versions are verified like downloaded ones.
+    ///
+    /// # Arguments
+    ///
+    /// * `paths` - files and directories to search, directories are searched recursively
+    /// * `search_path` - also search directories listed in `PATH`, without their subdirectories
+    pub fn import(&self, paths: &[PathBuf], search_path: bool) -> Result<ScanReport, Error> {
+        let mut candidates = local::find_binaries(paths, local::SCAN_DEPTH);
+        if search_path {
+            // `PATH` entries like `/usr/bin` are large trees, only the binaries directly in them are considered.
+            candidates.extend(local::find_binaries(&local::path_dirs(), 0));
+        }
+
+        // The `resolc` wrapper installed next to `rvm` runs the default version, it's not a build on its own.
+        let store = std::fs::canonicalize(self.fs.path())?;
+        let wrapper = std::env::current_exe()
+            .ok()
+            .and_then(|exe| std::fs::canonicalize(exe.with_file_name("resolc")).ok());
+        let mut seen = BTreeSet::new();
+
+        let mut report = ScanReport::default();
+        for candidate in candidates {
+            let Ok(path) = std::fs::canonicalize(&candidate) else {
+                continue;
+            };
+            if path.starts_with(&store) || Some(&path) == wrapper.as_ref() || !seen.insert(path) {
+                continue;
+            }
+            let Ok(Probe { version, .. }) = local::probe(&candidate) else {
+                continue;
+            };
+
+            let binary = std::fs::read(&candidate)?;
+            let checksum = releases::sha256(&binary);
+            let Some(build) = self
+                .releases
+                .builds
+                .iter()
+                .find(|build| build.sha256.eq_ignore_ascii_case(&checksum))
+            else {
+                report.unknown.push((candidate, version));
+                continue;
+            };
+
+            if self.is_installed(&build.version) {
+                report.installed.push((candidate, build.version.clone()));
+            } else {
+                self.fs.install_version(build, &binary)?;
+                report.imported.push((candidate, build.version.clone()));
+            }
+        }
+
+        Ok(report)
+    }
+
+    /// Publishes an installed version to an OCI registry
+    ///
+    /// # Arguments
+    ///
+    /// * `version` - installed Resolc version
+    /// * `registry` - name of a configured OCI registry
+    pub fn push(&self, version: &Version, registry: &str) -> Result<(), Error> {
+        let config = self.fs.read_config()?;
+        let registry = config
+            .registries
+            .iter()
+            .find(|item| item.name == registry)
+            .ok_or_else(|| Error::UnknownRegistry {
+                name: registry.to_owned(),
+            })?;
+        if !OciRepository::is_oci(&registry.url) {
+            return Err(Error::PushNotSupported {
+                name: registry.name.clone(),
+            });
+        }
+
+        let build = self.fs.installed_build(version)?;
+        let binary = std::fs::read(self.fs.path().join(version.to_string()).join(&build.name))?;
+        OciRepository::from_url(&registry.url, &Platform::get()?)?.push(&build, &binary)
+    }
+
     /// Returns the version used by default
     pub fn get_default(&self) -> Result<Binary, Error> {
         let version = self.fs.get_default_version().map_err(|e| match e {
@@ -203,6 +1014,192 @@ impl VersionManager {
         self.fs.set_default_version(version)
     }
 
+    /// Returns the changes of the default version, oldest change first
+    pub fn default_history(&self) -> Result<Vec<DefaultChange>, Error> {
+        self.fs.default_history()
+    }
+
+    /// Switches the default back to the most recent previous default that is still installed, like `cd -`
+    pub fn use_previous(&self) -> Result<Binary, Error> {
+        let current = self.fs.get_default_version().ok();
+        let history = self.fs.default_history()?;
+        let previous = history::previous(&history, current.as_ref(), |version| {
+            self.is_installed(version)
+        })
+        .ok_or(Error::NoPreviousDefault)?
+        .clone();
+        self.set_default(&previous)?;
+        self.get(&previous, None)
+    }
+
+    /// Returns the installed binary for a version, a version specification or the name of a linked binary
+    ///
+    /// Specifications select the newest installed version matching them, e.g. `latest-nightly` or `^0.3`.
+    pub fn select(&self, selector: &str) -> Result<Binary, Error> {
+        if let Ok(version) = selector.parse::<Version>() {
+            if !self.is_installed(&version) {
+                return Err(Error::NotInstalled { version });
+            }
+            return self.get(&version, None);
+        }
+        let spec = if local::validate_link_name(selector).is_ok() {
+            match self.get_linked(selector) {
+                Err(err @ Error::UnknownLink { .. }) => {
+                    self.fs.read_config()?.alias(selector).ok_or(err)?
+                }
+                linked => return linked,
+            }
+        } else {
+            selector.parse::<VersionSpec>()?
+        };
+        let installed: Vec<Version> = self
+            .fs
+            .installed_versions()?
+            .into_iter()
+            .filter(|build| !build.is_link())
+            .map(|build| build.version)
+            .collect();
+        match spec.select(&installed) {
+            Some(version) => self.get(version, None),
+            None => Err(Error::NoMatchingVersion {
+                spec: selector.to_owned(),
+            }),
+        }
+    }
+
+    /// Lists the Resolc versions requested for the directory, by precedence
+    ///
+    /// # Arguments
+    ///
+    /// * `argument` - selector passed as `resolc +<selector>`
+    /// * `dir` - directory `.resolc-version` project files are looked up from
+    pub fn candidates(&self, argument: Option<&str>, dir: &Path) -> Result<Vec<Candidate>, Error> {
+        resolve::candidates(
+            self.fs.as_ref(),
+            argument,
+            &|variable| std::env::var(variable).ok(),
+            dir,
+            self.fs.get_default_version().ok(),
+        )
+    }
+
+    /// Returns the version pinned by the closest `.resolc-version` project file of the directory, if there's one
+    pub fn project_version(dir: &Path) -> Result<Option<Candidate>, Error> {
+        resolve::project_file(dir)
+    }
+
+    /// Finds the binary `resolc` runs in the directory using only the store index, without loading release metadata.
+    ///
+    /// Returns `None` when [`VersionManager::resolve`] is needed, e.g. for version specifications or versions that
+    /// aren't installed.
+    ///
+    /// # Arguments
+    ///
+    /// * `argument` - selector passed as `resolc +<selector>`
+    /// * `dir` - directory `.resolc-version` project files are looked up from
+    pub fn resolve_indexed(argument: Option<&str>, dir: &Path) -> Option<PathBuf> {
+        resolve::indexed(argument, dir)
+    }
+
+    /// Finds the binary `resolc` runs in the directory and the rule that selected it
+    ///
+    /// # Arguments
+    ///
+    /// * `argument` - selector passed as `resolc +<selector>`
+    /// * `dir` - directory `.resolc-version` project files are looked up from
+    pub fn resolve(&self, argument: Option<&str>, dir: &Path) -> Result<Resolution, Error> {
+        let mut candidates = self.candidates(argument, dir)?.into_iter();
+        let selected = candidates.next().ok_or(Error::DefaultVersionNotSet)?;
+        let binary = if selected.is_path() {
+            self.external_build(Path::new(&selected.selector))
+                .and_then(|build| {
+                    let path = file_path(&build.url)?;
+                    Ok(build.into_external(path))
+                })
+        } else {
+            self.select(&selected.selector)
+        }
+        .map_err(|err| Error::SelectionFailed {
+            selector: selected.selector.clone(),
+            origin: selected.source.to_string(),
+            reason: Box::new(err),
+        })?;
+        Ok(Resolution {
+            selected,
+            shadowed: candidates.collect(),
+            binary,
+        })
+    }
+
+    /// Returns full metadata of the version, combining its release with the local installation.
+    ///
+    /// The metadata of the installed build wins over the release, so versions from removed registries are described as well.
+    pub fn info(&self, version: &Version) -> Result<VersionInfo, Error> {
+        let installed = self.fs.installed_build(version).ok();
+        let build = match &installed {
+            Some(build) => build.clone(),
+            None => self.releases.get_build(version)?.clone(),
+        };
+        let installation = match installed {
+            Some(build) => {
+                let path = self.fs.path().join(version.to_string()).join(&build.name);
+                let binary = std::fs::read(&path)?;
+                let dir = self.fs.path().join(version.to_string());
+                let metadata = usage::InstallMetadata::read(&dir);
+                Some(Installation {
+                    installed_at: self.fs.installed_at(&version.to_string()),
+                    last_used: self.fs.last_used(&version.to_string()),
+                    source: metadata.as_ref().map(|metadata| metadata.source.clone()),
+                    size: binary.len() as u64,
+                    verified: build.verify_binary(&binary).is_ok(),
+                    path,
+                })
+            }
+            None => None,
+        };
+        let is_default = self
+            .fs
+            .get_default_version()
+            .is_ok_and(|default| default == *version);
+
+        Ok(VersionInfo::new(build, installation, is_default))
+    }
+
+    /// Returns when the binary was installed, or linked for linked binaries.
+    ///
+    /// Always `None` for remote binaries.
+    pub fn installed_at(&self, binary: &Binary) -> Option<SystemTime> {
+        match binary {
+            Binary::Local { info, .. } => self.fs.installed_at(
+                &info
+                    .link
+                    .clone()
+                    .unwrap_or_else(|| info.version.to_string()),
+            ),
+            Binary::Remote(_) => None,
+        }
+    }
+
+    /// Returns when the binary was last run by the `resolc` wrapper.
+    ///
+    /// Always `None` for remote binaries, linked binaries and versions that were never run.
+    pub fn last_used(&self, binary: &Binary) -> Option<SystemTime> {
+        match binary {
+            Binary::Local { info, .. } if info.link.is_none() => {
+                self.fs.last_used(&info.version.to_string())
+            }
+            _ => None,
+        }
+    }
+
+    /// Records that the binary is being run, used by the `resolc` wrapper.
+    ///
+    /// Only binaries installed in the store are tracked. The write is skipped when the last recorded use is recent,
+    /// and it takes no lock, so it's cheap enough to do on every compiler run.
+    pub fn record_use(binary: &Path) {
+        usage::record_use(binary)
+    }
+
     /// Lists all installed and available Resolc versions
     ///
     /// # Arguments
@@ -225,7 +1222,10 @@ impl VersionManager {
                 }
             })
             .map(|x| {
-                installed_versions.insert(x.version.clone());
+                // Linked binaries don't hide the release of the same version.
+                if !x.is_link() {
+                    installed_versions.insert(x.version.clone());
+                }
                 Ok::<releases::Binary, Error>(x.into_local(self.fs.path()))
             })
             .collect();
@@ -254,7 +1254,10 @@ mod test {
     use expect_test::expect;
     use semver::Version;
 
-    use crate::{Binary, Error, FsPaths, VersionManager};
+    use crate::{
+        Binary, Channel, Config, Error, FsPaths, Platform, PrunePolicy, Registry, Releases, Source,
+        UpgradeTarget, VersionManager, LOCK_FILE_NAME, OFFICIAL_REGISTRY, PROJECT_FILE_NAME,
+    };
 
     /// Temp directory storage
     #[derive(Clone)]
@@ -603,6 +1606,682 @@ mod test {
         expected.assert_eq(&format!("{result:#?}"));
     }
 
+    fn build(version: &str, binary: &[u8]) -> crate::Build {
+        serde_json::from_value(serde_json::json!({
+            "name": "resolc",
+            "version": version,
+            "longVersion": format!("{version}+commit.0000000"),
+            "url": "https://example.com/resolc",
+            "sha256": crate::releases::sha256(binary),
+            "firstSolcVersion": "0.8.0",
+            "lastSolcVersion": "0.8.30"
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn version_info() {
+        let binary = b"resolc";
+        let build = build("0.4.0", binary);
+        let fs = TempDir::new().unwrap();
+        let manager = VersionManager {
+            offline: true,
+            fs: Box::new(fs.clone()),
+            releases: Releases::from_builds(vec![build.clone()]),
+        };
+
+        let version = Version::new(0, 4, 0);
+        let info = manager.info(&version).unwrap();
+        assert_eq!(info.commit.as_deref(), Some("0000000"));
+        assert_eq!(info.installation, None);
+        assert!(!info.is_default);
+
+        fs.install_version(&build, binary).unwrap();
+        fs.set_default_version(&version).unwrap();
+        let installation = manager.info(&version).unwrap().installation.unwrap();
+        assert!(installation.verified);
+        assert_eq!(installation.size, binary.len() as u64);
+        assert_eq!(installation.source.as_ref(), Some(&build.url));
+        assert!(installation.installed_at.is_some());
+        assert_eq!(installation.last_used, None);
+        assert!(manager.info(&version).unwrap().is_default);
+
+        VersionManager::record_use(&installation.path);
+        let installed = manager.get(&version, None).unwrap();
+        assert!(manager.last_used(&installed).is_some());
+
+        std::fs::write(&installation.path, b"tampered").unwrap();
+        assert!(
+            !manager
+                .info(&version)
+                .unwrap()
+                .installation
+                .unwrap()
+                .verified
+        );
+        assert!(manager.info(&Version::new(0, 5, 0)).is_err());
+    }
+
+    #[test]
+    fn select_installed() {
+        let fs = TempDir::new().unwrap();
+        let builds = vec![
+            build("0.3.0", b"0.3.0"),
+            build("0.4.0", b"0.4.0"),
+            build("0.5.0", b"0.5.0"),
+        ];
+        for build in &builds[..2] {
+            fs.install_version(build, build.version.to_string().as_bytes())
+                .unwrap();
+        }
+        let manager = VersionManager {
+            offline: true,
+            fs: Box::new(fs),
+            releases: Releases::from_builds(builds),
+        };
+
+        let selected = |selector: &str| manager.select(selector).map(|b| b.version().to_string());
+        assert_eq!(selected("0.3.0").unwrap(), "0.3.0");
+        assert_eq!(selected("^0.3").unwrap(), "0.3.0");
+        assert_eq!(selected("latest").unwrap(), "0.4.0");
+        assert!(matches!(selected("0.5.0"), Err(Error::NotInstalled { .. })));
+        assert!(matches!(
+            selected(">=0.5"),
+            Err(Error::NoMatchingVersion { .. })
+        ));
+        assert!(matches!(selected("dev"), Err(Error::UnknownLink { .. })));
+    }
+
+    #[test]
+    fn prune_keeps_default_and_project() {
+        let fs = TempDir::new().unwrap();
+        let versions = [
+            "0.3.0-nightly.2025.7.8",
+            "0.3.0-nightly.2025.7.9",
+            "0.3.0",
+            "0.4.0-nightly.2025.8.9",
+        ];
+        let builds: Vec<crate::Build> = versions
+            .iter()
+            .map(|version| build(version, version.as_bytes()))
+            .collect();
+        for build in &builds {
+            fs.install_version(build, build.version.to_string().as_bytes())
+                .unwrap();
+        }
+        fs.set_default_version(&builds[0].version).unwrap();
+        let manager = VersionManager {
+            offline: true,
+            fs: Box::new(fs),
+            releases: Releases::from_builds(builds),
+        };
+        let project = tempfile::tempdir().unwrap();
+        std::fs::write(project.path().join(PROJECT_FILE_NAME), "^0.4.0-nightly").unwrap();
+
+        let policy = PrunePolicy {
+            superseded: true,
+            matching: Some(">=0.4.0-nightly".parse().unwrap()),
+            ..Default::default()
+        };
+        let pruned = manager.prune(&policy, project.path(), true).unwrap();
+        assert_eq!(pruned, [Version::parse("0.3.0-nightly.2025.7.9").unwrap()]);
+        assert!(manager.is_installed(&pruned[0]));
+
+        manager.prune(&policy, project.path(), false).unwrap();
+        assert!(!manager.is_installed(&pruned[0]));
+        assert_eq!(manager.fs.installed_versions().unwrap().len(), 3);
+    }
+
+    #[test]
+    fn install_matching() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut builds = Vec::new();
+        for version in ["0.3.0", "0.4.0", "0.5.0-nightly.2025.8.9"] {
+            let path = dir.path().join(version);
+            std::fs::write(&path, version).unwrap();
+            let mut build = build(version, version.as_bytes());
+            build.url = url::Url::from_file_path(&path).unwrap();
+            builds.push(build);
+        }
+        let manager = VersionManager {
+            offline: false,
+            fs: Box::new(TempDir::new().unwrap()),
+            releases: Releases::from_builds(builds),
+        };
+
+        let installed = manager.get_or_install_matching("latest").unwrap();
+        assert_eq!(installed.version(), &Version::new(0, 4, 0));
+        assert!(manager.is_installed(&Version::new(0, 4, 0)));
+        assert!(matches!(
+            manager.get_or_install_matching("^1"),
+            Err(Error::NoMatchingVersion { .. })
+        ));
+    }
+
+    #[test]
+    fn lock_from_registry() {
+        let repository = tempfile::tempdir().unwrap();
+        let current = Platform::get().unwrap();
+        let other = Platform::ALL
+            .into_iter()
+            .find(|platform| *platform != current)
+            .unwrap();
+        let mut installed = build("0.4.0-patch.1", b"current");
+        installed.registry = Some("internal".to_owned());
+        let mut released = build("0.4.0-patch.1", b"other");
+        released.url = url::Url::parse("https://internal.example.com/other/resolc").unwrap();
+        std::fs::create_dir_all(repository.path().join(other.name())).unwrap();
+        std::fs::write(
+            repository.path().join(other.name()).join("list.json"),
+            serde_json::to_vec(&Releases::from_builds(vec![released.clone()])).unwrap(),
+        )
+        .unwrap();
+
+        let fs = TempDir::new().unwrap();
+        fs.install_version(&installed, b"current").unwrap();
+        let manager = VersionManager {
+            offline: false,
+            fs: Box::new(fs.clone()),
+            releases: Releases::from_builds(vec![installed.clone()]),
+        };
+        let project = tempfile::tempdir().unwrap();
+        let lock = |url: &str| {
+            let mut config = Config::default();
+            config.add_registry(Registry::new("internal", url.parse().unwrap(), 1).unwrap());
+            fs.write_config(&config).unwrap();
+            manager.lock(Some("0.4.0-patch.1"), project.path(), &[current, other])
+        };
+
+        // Other platforms come from the same release repository, not from the official releases.
+        let base = url::Url::from_directory_path(repository.path()).unwrap();
+        let (_, lockfile) = lock(base.as_str()).unwrap();
+        assert_eq!(lockfile.platforms[&other].url, released.url);
+        assert_eq!(lockfile.platforms[&other].sha256, released.sha256);
+
+        // A single release list has no builds of other platforms.
+        let list = base.join(&format!("{}/list.json", other.name())).unwrap();
+        assert!(matches!(
+            lock(list.as_str()),
+            Err(Error::NoPlatformReleases { .. })
+        ));
+    }
+
+    #[test]
+    fn lock_and_sync() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("resolc");
+        std::fs::write(&path, "0.4.0").unwrap();
+        let mut build = build("0.4.0", b"0.4.0");
+        build.url = url::Url::from_file_path(&path).unwrap();
+        let fs = TempDir::new().unwrap();
+        fs.install_version(&build, b"0.4.0").unwrap();
+        let manager = |offline| VersionManager {
+            offline,
+            fs: Box::new(fs.clone()),
+            releases: Releases::from_builds(vec![build.clone()]),
+        };
+        let project = tempfile::tempdir().unwrap();
+        assert!(matches!(
+            manager(true).sync(project.path()),
+            Err(Error::NoLockfile)
+        ));
+
+        let (lock, lockfile) = manager(true)
+            .lock(Some("0.4.0"), project.path(), &[])
+            .unwrap();
+        assert_eq!(lock, project.path().join(LOCK_FILE_NAME));
+        assert_eq!(lockfile.platforms.len(), 1);
+        let candidate = VersionManager::project_version(project.path())
+            .unwrap()
+            .unwrap();
+        assert_eq!(candidate.source, Source::LockFile { path: lock });
+        assert_eq!(candidate.selector, "0.4.0");
+
+        let installed = fs.path().join("0.4.0").join("resolc");
+        manager(true).sync(project.path()).unwrap();
+        std::fs::write(&installed, "tampered").unwrap();
+        assert!(matches!(
+            manager(true).sync(project.path()),
+            Err(Error::LockfileMismatch { .. })
+        ));
+
+        fs.remove_version(&Version::new(0, 4, 0)).unwrap();
+        assert!(matches!(
+            manager(true).sync(project.path()),
+            Err(Error::CantInstallOffline)
+        ));
+        let synced = manager(false).sync(project.path()).unwrap();
+        assert_eq!(std::fs::read(synced.local().unwrap()).unwrap(), b"0.4.0");
+
+        // Upstream binaries that changed since locking are rejected.
+        fs.remove_version(&Version::new(0, 4, 0)).unwrap();
+        std::fs::write(&path, "rebuilt").unwrap();
+        assert!(matches!(
+            manager(false).sync(project.path()),
+            Err(Error::ChecksumValidationError { .. })
+        ));
+    }
+
+    #[test]
+    fn tool_config() {
+        let builds = vec![build("0.3.0", b"0.3.0"), build("0.4.0", b"0.4.0")];
+        let fs = TempDir::new().unwrap();
+        for build in &builds {
+            fs.install_version(build, build.version.to_string().as_bytes())
+                .unwrap();
+        }
+        fs.set_default_version(&Version::new(0, 4, 0)).unwrap();
+        let manager = VersionManager {
+            offline: true,
+            fs: Box::new(fs.clone()),
+            releases: Releases::from_builds(builds),
+        };
+        let project = tempfile::tempdir().unwrap();
+        let foundry = project.path().join("foundry.toml");
+        std::fs::write(
+            &foundry,
+            "[profile.default.resolc]\nresolc = \"0.3.0\"\n\n[profile.ci.resolc]\nresolc = \"^0.4\"\n\n[profile.local.resolc]\nresolc = \"bin/resolc\"\n",
+        )
+        .unwrap();
+
+        // Project configuration is opt-in.
+        let resolution = manager.resolve(None, project.path()).unwrap();
+        assert_eq!(resolution.binary.version(), &Version::new(0, 4, 0));
+
+        fs.write_config(&Config {
+            project_configs: true,
+            ..Config::default()
+        })
+        .unwrap();
+        let resolution = manager.resolve(None, project.path()).unwrap();
+        assert_eq!(resolution.binary.version(), &Version::new(0, 3, 0));
+        assert!(matches!(
+            resolution.selected.source,
+            Source::ToolConfig { ref path, .. } if path == &foundry
+        ));
+
+        let synced: Vec<_> = manager
+            .sync_from(&foundry)
+            .unwrap()
+            .iter()
+            .map(|binary| binary.version().clone())
+            .collect();
+        assert_eq!(synced, [Version::new(0, 3, 0), Version::new(0, 4, 0)]);
+    }
+
+    #[test]
+    fn sources() {
+        let mut old = build("0.3.0", b"0.3.0");
+        old.last_supported_solc_version = Version::new(0, 8, 28);
+        let mut new = build("0.4.0", b"0.4.0");
+        new.first_supported_solc_version = Version::new(0, 8, 20);
+        let fs = TempDir::new().unwrap();
+        fs.install_version(&old, b"0.3.0").unwrap();
+        let manager = VersionManager {
+            offline: true,
+            fs: Box::new(fs),
+            releases: Releases::from_builds(vec![old, new]),
+        };
+        let contracts = tempfile::tempdir().unwrap();
+        let sources = [contracts.path().join("Token.sol")];
+        let source = &sources[0];
+        std::fs::write(source, "pragma solidity >=0.8.20 <=0.8.29;").unwrap();
+
+        let resolution = manager
+            .resolve_sources(&[contracts.path().to_path_buf()], None, false)
+            .unwrap();
+        assert_eq!(resolution.resolc, Version::new(0, 4, 0));
+        assert_eq!(resolution.solc, Version::new(0, 8, 29));
+        assert!(!resolution.installed);
+        assert!(matches!(
+            manager.resolve_sources(&sources, None, true),
+            Err(Error::CantInstallOffline)
+        ));
+
+        // Older releases are picked when the newest ones don't support any matching `solc` version.
+        std::fs::write(source, "pragma solidity ^0.8.0 <0.8.20;").unwrap();
+        let resolution = manager.resolve_sources(&sources, None, true).unwrap();
+        assert_eq!(resolution.resolc, Version::new(0, 3, 0));
+        assert_eq!(resolution.solc, Version::new(0, 8, 19));
+        assert!(resolution.installed);
+
+        std::fs::write(source, "pragma solidity ^0.7.0;").unwrap();
+        assert!(matches!(
+            manager.resolve_sources(&sources, None, false),
+            Err(Error::NoCompatibleCompiler)
+        ));
+        std::fs::write(source, "pragma solidity >>0.8;").unwrap();
+        assert!(matches!(
+            manager.resolve_sources(&sources, None, false),
+            Err(Error::InvalidPragma { .. })
+        ));
+    }
+
+    #[test]
+    fn aliases() {
+        let builds = vec![build("0.3.0", b"0.3.0"), build("0.4.0", b"0.4.0")];
+        let fs = TempDir::new().unwrap();
+        fs.install_version(&builds[0], b"0.3.0").unwrap();
+        let manager = VersionManager {
+            offline: true,
+            fs: Box::new(fs.clone()),
+            releases: Releases::from_builds(builds),
+        };
+
+        let alias = manager.set_alias("prod", "^0.3").unwrap();
+        assert_eq!(alias.version, Some(Version::new(0, 3, 0)));
+        assert_eq!(
+            manager.select("prod").unwrap().version(),
+            &Version::new(0, 3, 0)
+        );
+        let index = fs.read_index().unwrap();
+        assert_eq!(index.binary("prod"), index.binary("0.3.0"));
+
+        manager.set_alias("prod", "0.4.0").unwrap();
+        assert!(matches!(
+            manager.select("prod"),
+            Err(Error::NoMatchingVersion { .. })
+        ));
+        assert_eq!(fs.read_index().unwrap().binary("prod"), None);
+        assert!(matches!(
+            manager.set_alias("0.4.0", "0.3.0"),
+            Err(Error::InvalidAliasName { .. })
+        ));
+        assert!(matches!(
+            manager.set_alias("prod", "not a version"),
+            Err(Error::InvalidVersionSpec { .. })
+        ));
+
+        assert_eq!(manager.remove_alias("prod").unwrap(), "0.4.0");
+        assert!(manager.aliases().unwrap().is_empty());
+        assert!(matches!(
+            manager.select("prod"),
+            Err(Error::UnknownLink { .. })
+        ));
+    }
+
+    #[test]
+    fn registries() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut internal = build("0.3.0", b"internal");
+        internal.url = url::Url::from_file_path(dir.path().join("resolc")).unwrap();
+        std::fs::write(dir.path().join("resolc"), b"internal").unwrap();
+        let list = dir.path().join("list.json");
+        std::fs::write(
+            &list,
+            serde_json::to_vec(&Releases::from_builds(vec![internal])).unwrap(),
+        )
+        .unwrap();
+        let registries = [
+            Registry::new("internal", url::Url::from_file_path(&list).unwrap(), 1).unwrap(),
+            Registry::new(
+                "broken",
+                url::Url::from_file_path(dir.path().join("missing.json")).unwrap(),
+                1,
+            )
+            .unwrap(),
+        ];
+        let official = Releases::from_builds(vec![build("0.3.0", b"official")])
+            .with_registry(OFFICIAL_REGISTRY);
+
+        // Unreachable registries are skipped instead of failing the whole release list.
+        let releases = VersionManager::with_registries(official, &registries);
+        assert_eq!(releases.builds.len(), 2);
+        assert_eq!(releases.unavailable.len(), 1);
+        assert_eq!(releases.unavailable[0].name, "broken");
+
+        let fs = TempDir::new().unwrap();
+        let official = releases
+            .get_build_in(Some(OFFICIAL_REGISTRY), &Version::new(0, 3, 0))
+            .unwrap();
+        fs.install_version(official, b"official").unwrap();
+        let manager = VersionManager {
+            offline: false,
+            fs: Box::new(fs),
+            releases,
+        };
+        let version = Version::new(0, 3, 0);
+        manager
+            .get_or_install_from(Some(OFFICIAL_REGISTRY), &version, None)
+            .unwrap();
+        assert!(matches!(
+            manager.get_or_install_from(Some("internal"), &version, None),
+            Err(Error::InstalledFromOtherRegistry { ref installed, .. }) if installed == OFFICIAL_REGISTRY
+        ));
+        // The internal build has the higher priority, but the installed one is reported.
+        assert_eq!(
+            manager
+                .get(&version, None)
+                .unwrap()
+                .info()
+                .registry
+                .as_deref(),
+            Some(OFFICIAL_REGISTRY)
+        );
+    }
+
+    #[test]
+    fn interrupted_install() {
+        struct Interrupted;
+        impl std::io::Read for Interrupted {
+            fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+                Err(std::io::Error::other("connection reset"))
+            }
+        }
+
+        let fs = TempDir::new().unwrap();
+        let build = build("0.4.0", b"0.4.0");
+        assert!(fs.install_version_from(&build, &mut Interrupted).is_err());
+        assert!(fs.installed_build(&build.version).is_err());
+        assert!(matches!(
+            fs.install_version(&build, b"0.4"),
+            Err(Error::ChecksumValidationError { .. })
+        ));
+        assert!(fs.installed_versions().unwrap().is_empty());
+
+        fs.install_version(&build, b"0.4.0").unwrap();
+        assert_eq!(fs.installed_build(&build.version).unwrap(), build);
+    }
+
+    #[test]
+    fn default_rollback() {
+        let builds = vec![build("0.3.0", b"0.3.0"), build("0.4.0", b"0.4.0")];
+        let fs = TempDir::new().unwrap();
+        for build in &builds {
+            fs.install_version(build, build.version.to_string().as_bytes())
+                .unwrap();
+        }
+        let manager = VersionManager {
+            offline: true,
+            fs: Box::new(fs),
+            releases: Releases::from_builds(builds),
+        };
+        assert!(matches!(
+            manager.use_previous(),
+            Err(Error::NoPreviousDefault)
+        ));
+
+        manager.set_default(&Version::new(0, 3, 0)).unwrap();
+        manager.set_default(&Version::new(0, 4, 0)).unwrap();
+        let previous = manager.use_previous().unwrap();
+        assert_eq!(previous.version(), &Version::new(0, 3, 0));
+        assert_eq!(
+            manager.get_default().unwrap().version(),
+            &Version::new(0, 3, 0)
+        );
+        let history: Vec<String> = manager
+            .default_history()
+            .unwrap()
+            .iter()
+            .map(|change| change.version.to_string())
+            .collect();
+        assert_eq!(history, ["0.3.0", "0.4.0", "0.3.0"]);
+
+        // Removed versions are skipped, rolling back further.
+        manager.set_default(&Version::new(0, 4, 0)).unwrap();
+        manager.fs.remove_version(&Version::new(0, 3, 0)).unwrap();
+        assert!(matches!(
+            manager.use_previous(),
+            Err(Error::NoPreviousDefault)
+        ));
+    }
+
+    #[test]
+    fn outdated_and_upgrade() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut builds = Vec::new();
+        for version in ["0.3.0", "0.3.1", "0.4.0", "0.5.0-nightly.2025.8.9"] {
+            let path = dir.path().join(version);
+            std::fs::write(&path, version).unwrap();
+            let mut build = build(version, version.as_bytes());
+            build.url = url::Url::from_file_path(&path).unwrap();
+            builds.push(build);
+        }
+        let fs = TempDir::new().unwrap();
+        fs.install_version(&builds[0], b"0.3.0").unwrap();
+        fs.set_default_version(&builds[0].version).unwrap();
+        let manager = VersionManager {
+            offline: false,
+            fs: Box::new(fs),
+            releases: Releases::from_builds(builds),
+        };
+        let project = tempfile::tempdir().unwrap();
+
+        let outdated = manager.outdated(project.path()).unwrap();
+        assert_eq!(outdated.len(), 1);
+        assert!(outdated[0].is_default);
+        assert_eq!(outdated[0].compatible, Some(Version::new(0, 3, 1)));
+        assert_eq!(outdated[0].latest, Some(Version::new(0, 4, 0)));
+
+        assert!(matches!(
+            manager.upgrade(None, UpgradeTarget::Project, project.path(), false),
+            Err(Error::NoProjectFile)
+        ));
+        let upgrade = manager
+            .upgrade(None, UpgradeTarget::Default, project.path(), true)
+            .unwrap();
+        assert_eq!(upgrade.from, Some(Version::new(0, 3, 0)));
+        assert_eq!(upgrade.to.version(), &Version::new(0, 4, 0));
+        assert!(upgrade.pruned);
+        assert!(!manager.is_installed(&Version::new(0, 3, 0)));
+        assert_eq!(
+            manager.get_default().unwrap().version(),
+            &Version::new(0, 4, 0)
+        );
+
+        let file = project.path().join(PROJECT_FILE_NAME);
+        std::fs::write(&file, "0.4.0\n").unwrap();
+        let upgrade = manager
+            .upgrade(
+                Some(Channel::Nightly),
+                UpgradeTarget::Project,
+                project.path(),
+                true,
+            )
+            .unwrap();
+        assert_eq!(upgrade.project.as_ref(), Some(&file));
+        // The previous version is still the default.
+        assert!(!upgrade.pruned);
+        assert_eq!(
+            std::fs::read_to_string(&file).unwrap(),
+            "0.5.0-nightly.2025.8.9\n"
+        );
+        assert!(manager
+            .outdated(project.path())
+            .unwrap()
+            .iter()
+            .all(|version| !version.is_outdated()));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn link_and_unlink() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let manager = VersionManager {
+            offline: true,
+            fs: Box::new(TempDir::new().unwrap()),
+            releases: Releases::from_builds(vec![]),
+        };
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("resolc");
+        std::fs::write(
+            &path,
+            "#!/bin/sh\necho 'Solidity frontend for the revive compiler version 0.5.0-dev.1+commit.0000000'\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        manager.link("dev", &path).unwrap();
+        let linked = manager.get_linked("dev").unwrap();
+        assert_eq!(linked.version(), &Version::parse("0.5.0-dev.1").unwrap());
+        assert_eq!(
+            linked.local(),
+            Some(std::fs::canonicalize(&path).unwrap().as_path())
+        );
+        assert!(manager.installed_at(&linked).is_some());
+        assert_eq!(manager.list_available(None).unwrap(), vec![linked]);
+        assert!(manager.link("0.5.0", &path).is_err());
+
+        manager.unlink("dev").unwrap();
+        assert!(path.exists());
+        assert!(manager.get_linked("dev").is_err());
+        assert!(manager.list_available(None).unwrap().is_empty());
+
+        // Names of the store locks don't block the link on its own lock.
+        for name in ["index", "config"] {
+            manager.link(name, &path).unwrap();
+            assert!(manager.get_linked(name).is_ok());
+            manager.unlink(name).unwrap();
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn import_found_binaries() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let script = |name: &str, version: &str| {
+            let path = dir.path().join(name);
+            std::fs::write(
+                &path,
+                format!("#!/bin/sh\necho 'Solidity frontend for the revive compiler version {version}'\n"),
+            )
+            .unwrap();
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+            path
+        };
+        let known = script("resolc-x86_64-unknown-linux-musl", "0.4.0+commit.0000000");
+        let unknown = script("resolc", "0.4.0+commit.1111111");
+        script("solc", "0.4.0+commit.0000000");
+
+        let build: crate::Build = serde_json::from_value(serde_json::json!({
+            "name": "resolc-x86_64-unknown-linux-musl",
+            "version": "0.4.0",
+            "longVersion": "0.4.0+commit.0000000",
+            "url": "https://example.com/resolc",
+            "sha256": crate::releases::sha256(&std::fs::read(&known).unwrap()),
+            "firstSolcVersion": "0.8.0",
+            "lastSolcVersion": "0.8.30"
+        }))
+        .unwrap();
+        let manager = VersionManager {
+            offline: false,
+            fs: Box::new(TempDir::new().unwrap()),
+            releases: Releases::from_builds(vec![build]),
+        };
+
+        let version = Version::new(0, 4, 0);
+        let report = manager.import(&[dir.path().to_path_buf()], false).unwrap();
+        assert_eq!(report.imported, vec![(known.clone(), version.clone())]);
+        assert_eq!(report.unknown, vec![(unknown, version.clone())]);
+        assert!(manager.get(&version, None).is_ok());
+
+        let report = manager.import(std::slice::from_ref(&known), false).unwrap();
+        assert_eq!(report.installed, vec![(known, version)]);
+    }
+
     #[test]
     fn concurrent() {
         let temp_dir = TempDir::new().unwrap();
@@ -611,7 +2290,7 @@ mod test {
             let manager = VersionManager {
                 offline: false,
                 fs: Box::new(temp_dir),
-                releases: VersionManager::get_releases().expect("no network"),
+                releases: VersionManager::get_releases(&Config::default()).expect("no network"),
             };
             manager
                 .get_or_install(&semver::Version::parse("0.1.0-dev.13").unwrap(), None)
@@ -621,7 +2300,7 @@ mod test {
             let manager2 = VersionManager {
                 offline: false,
                 fs: Box::new(temp_dir2),
-                releases: VersionManager::get_releases().expect("no network"),
+                releases: VersionManager::get_releases(&Config::default()).expect("no network"),
             };
             manager2
                 .get_or_install(&semver::Version::parse("0.1.0-dev.13").unwrap(), None)
@@ -0,0 +1,213 @@
+use std::{
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+use semver::Version;
+use serde::{Deserialize, Serialize};
+
+use crate::errors::Error;
+
+/// External Resolc binary registered in the store with `rvm link`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct Link {
+    /// Name the binary is registered under
+    pub(crate) name: String,
+    /// Path to the external binary, it's never modified by the version manager
+    pub(crate) path: PathBuf,
+}
+
+/// Outcome of importing Resolc binaries found on the system.
+#[derive(Debug, Default)]
+pub struct ScanReport {
+    /// Binaries that matched a release and were installed
+    pub imported: Vec<(PathBuf, Version)>,
+    /// Binaries that matched a release that is installed already
+    pub installed: Vec<(PathBuf, Version)>,
+    /// Binaries that report a Resolc version but don't match any release
+    pub unknown: Vec<(PathBuf, Version)>,
+}
+
+/// Identity of a Resolc binary as reported by `resolc --version`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Probe {
+    /// Version without build metadata
+    pub(crate) version: Version,
+    /// Full version including build metadata, e.g. `0.1.0-dev.13+commit.ad33153.llvm-18.1.8`
+    pub(crate) long_version: String,
+}
+
+/// Runs the binary with `--version` to find out which Resolc version it is.
+pub(crate) fn probe(path: &Path) -> Result<Probe, Error> {
+    let unrecognized = || Error::UnrecognizedBinary {
+        path: path.to_path_buf(),
+    };
+    let output = Command::new(path)
+        .arg("--version")
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()?;
+    if !output.status.success() {
+        return Err(unrecognized());
+    }
+
+    parse_version_output(&String::from_utf8_lossy(&output.stdout)).ok_or_else(unrecognized)
+}
+
+/// Parses `Solidity frontend for the revive compiler version <version>`
+fn parse_version_output(output: &str) -> Option<Probe> {
+    let line = output
+        .lines()
+        .find(|line| line.contains("revive compiler version"))?;
+    let long_version = line.split_whitespace().last()?;
+    let mut version = Version::parse(long_version).ok()?;
+    version.build = semver::BuildMetadata::EMPTY;
+
+    Some(Probe {
+        version,
+        long_version: long_version.to_owned(),
+    })
+}
+
+/// Maximum depth of directories searched for Resolc binaries.
+pub(crate) const SCAN_DEPTH: usize = 8;
+
+/// Finds executables that look like Resolc binaries: `resolc*` files in the directories
+/// and the given files themselves.
+///
+/// # Arguments
+///
+/// * `depth` - levels of subdirectories to search, `0` only searches the directories themselves, e.g. for `PATH`
+pub(crate) fn find_binaries(paths: &[PathBuf], depth: usize) -> Vec<PathBuf> {
+    let mut found = Vec::new();
+    for path in paths {
+        if path.is_dir() {
+            walk(path, depth, &mut found);
+        } else if is_executable(path) {
+            found.push(path.clone());
+        }
+    }
+    found
+}
+
+/// Directories listed in `PATH`
+pub(crate) fn path_dirs() -> Vec<PathBuf> {
+    std::env::var_os("PATH")
+        .map(|paths| std::env::split_paths(&paths).collect())
+        .unwrap_or_default()
+}
+
+fn walk(dir: &Path, depth: usize, found: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.filter_map(|entry| entry.ok()) {
+        let path = entry.path();
+        let Ok(file_type) = entry.file_type() else {
+            continue;
+        };
+        // Symlinked directories are skipped to avoid loops, symlinked binaries are followed.
+        if file_type.is_dir() {
+            if depth > 0 {
+                walk(&path, depth - 1, found);
+            }
+        } else if entry.file_name().to_string_lossy().starts_with("resolc") && is_executable(&path)
+        {
+            found.push(path);
+        }
+    }
+}
+
+fn is_executable(path: &Path) -> bool {
+    let Ok(metadata) = std::fs::metadata(path) else {
+        return false;
+    };
+    if !metadata.is_file() {
+        return false;
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        metadata.permissions().mode() & 0o111 != 0
+    }
+    #[cfg(not(unix))]
+    {
+        path.extension().is_some_and(|ext| ext == "exe")
+    }
+}
+
+/// Checks whether the name can be used for a linked binary.
+///
+/// Names can't be versions or version specifications, so they are never ambiguous in `resolc +<name>`,
+/// and can't clash with the shim folder of the store.
+pub(crate) fn validate_link_name(name: &str) -> Result<(), Error> {
+    if is_valid_name(name) {
+        Ok(())
+    } else {
+        Err(Error::InvalidLinkName {
+            name: name.to_owned(),
+        })
+    }
+}
+
+/// Whether the name can be used for a linked binary or an alias, see [`validate_link_name`]
+pub(crate) fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name != crate::shims::SHIM_DIR
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'))
+        && name.starts_with(|c: char| c.is_ascii_alphabetic())
+        && name.parse::<crate::spec::VersionSpec>().is_err()
+}
+
+#[cfg(test)]
+mod test {
+    use semver::Version;
+
+    use super::{parse_version_output, validate_link_name};
+
+    #[test]
+    fn version_output() {
+        let probe = parse_version_output(
+            "Solidity frontend for the revive compiler version 0.1.0-dev.13+commit.ad33153.llvm-18.1.8\n",
+        )
+        .unwrap();
+        assert_eq!(probe.version, Version::parse("0.1.0-dev.13").unwrap());
+        assert_eq!(
+            probe.long_version,
+            "0.1.0-dev.13+commit.ad33153.llvm-18.1.8"
+        );
+
+        assert_eq!(parse_version_output("solc, the solidity compiler"), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn scan_depth() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let nested = dir.path().join("nested");
+        std::fs::create_dir(&nested).unwrap();
+        for path in [dir.path().join("resolc"), nested.join("resolc-dev")] {
+            std::fs::write(&path, "").unwrap();
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755)).unwrap();
+        }
+        let root = [dir.path().to_path_buf()];
+        assert_eq!(super::find_binaries(&root, 0), [dir.path().join("resolc")]);
+        assert_eq!(super::find_binaries(&root, super::SCAN_DEPTH).len(), 2);
+    }
+
+    #[test]
+    fn link_names() {
+        validate_link_name("dev").unwrap();
+        validate_link_name("my-build_2").unwrap();
+        assert!(validate_link_name("config.json").is_err());
+        assert!(validate_link_name("0.4.0").is_err());
+        assert!(validate_link_name("latest").is_err());
+        assert!(validate_link_name("bin").is_err());
+        assert!(validate_link_name(".default_version").is_err());
+        assert!(validate_link_name("../escape").is_err());
+    }
+}
@@ -0,0 +1,157 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::{constants::Platform, errors::Error, releases::Build};
+
+/// Name of the file that locks the Resolc binaries of a project, looked up next to project files.
+pub const LOCK_FILE_NAME: &str = "rvm.lock";
+
+const HEADER: &str = "# Generated by `rvm lock`, `rvm sync` installs exactly these binaries.\n\n";
+
+/// Exact Resolc build of a project with the binary of every locked platform, stored as `rvm.lock`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Lockfile {
+    /// Resolc version
+    pub version: Version,
+    /// Full version including build metadata
+    pub long_version: String,
+    /// first supported `solc` version
+    pub first_supported_solc_version: Version,
+    /// last supported `solc` version
+    pub last_supported_solc_version: Version,
+    /// Binaries by platform
+    pub platforms: BTreeMap<Platform, LockedBinary>,
+}
+
+/// Binary of a locked build for one platform.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LockedBinary {
+    /// File name of the binary
+    pub name: String,
+    /// URL the binary is downloaded from
+    pub url: Url,
+    /// sha256 of the binary
+    pub sha256: String,
+    /// Registry the binary comes from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub registry: Option<String>,
+}
+
+impl Lockfile {
+    /// Lockfile of the build, without any platform
+    pub(crate) fn new(build: &Build) -> Self {
+        Self {
+            version: build.version.clone(),
+            long_version: build.long_version.clone(),
+            first_supported_solc_version: build.first_supported_solc_version.clone(),
+            last_supported_solc_version: build.last_supported_solc_version.clone(),
+            platforms: BTreeMap::new(),
+        }
+    }
+
+    /// Locks the binary of the build for the platform, the build has to be of the locked version
+    pub(crate) fn insert(&mut self, platform: Platform, build: &Build) {
+        debug_assert_eq!(build.version, self.version);
+        self.platforms.insert(
+            platform,
+            LockedBinary {
+                name: build.name.clone(),
+                url: build.url.clone(),
+                sha256: build.sha256.clone(),
+                registry: build.registry.clone(),
+            },
+        );
+    }
+
+    /// Build of the locked binary for the platform
+    pub(crate) fn build(&self, platform: Platform) -> Result<Build, Error> {
+        let binary = self
+            .platforms
+            .get(&platform)
+            .ok_or_else(|| Error::PlatformNotLocked {
+                version: self.version.clone(),
+                platform,
+            })?;
+        Ok(Build {
+            name: binary.name.clone(),
+            version: self.version.clone(),
+            long_version: self.long_version.clone(),
+            url: binary.url.clone(),
+            first_supported_solc_version: self.first_supported_solc_version.clone(),
+            last_supported_solc_version: self.last_supported_solc_version.clone(),
+            sha256: binary.sha256.clone(),
+            registry: binary.registry.clone(),
+            link: None,
+        })
+    }
+
+    /// Reads the lockfile
+    pub fn read(path: &Path) -> Result<Self, Error> {
+        let lockfile = std::fs::read_to_string(path)?;
+        toml::from_str(&lockfile).map_err(|err| Error::InvalidLockfile {
+            path: path.to_path_buf(),
+            reason: err.message().to_owned(),
+        })
+    }
+
+    /// Writes the lockfile
+    pub fn write(&self, path: &Path) -> Result<(), Error> {
+        let lockfile = toml::to_string(self).map_err(|err| Error::InvalidLockfile {
+            path: path.to_path_buf(),
+            reason: err.to_string(),
+        })?;
+        std::fs::write(path, format!("{HEADER}{lockfile}")).map_err(Into::into)
+    }
+}
+
+/// Closest `rvm.lock` of the directory, looked up in the directory and its parents
+pub(crate) fn find(dir: &Path) -> Option<PathBuf> {
+    dir.ancestors()
+        .map(|dir| dir.join(LOCK_FILE_NAME))
+        .find(|path| path.is_file())
+}
+
+#[cfg(test)]
+mod test {
+    use super::{find, Lockfile, LOCK_FILE_NAME};
+    use crate::{constants::Platform, releases::Build};
+
+    #[test]
+    fn round_trip() {
+        let build: Build = serde_json::from_value(serde_json::json!({
+            "name": "resolc-x86_64-unknown-linux-musl",
+            "version": "0.4.0",
+            "longVersion": "0.4.0+commit.0000000",
+            "url": "https://example.com/resolc",
+            "sha256": crate::releases::sha256(b"binary"),
+            "firstSolcVersion": "0.8.0",
+            "lastSolcVersion": "0.8.30",
+            "registry": "official"
+        }))
+        .unwrap();
+        let mut lockfile = Lockfile::new(&build);
+        lockfile.insert(Platform::Linux, &build);
+
+        let project = tempfile::tempdir().unwrap();
+        let nested = project.path().join("contracts");
+        std::fs::create_dir(&nested).unwrap();
+        assert_eq!(find(&nested), None);
+        let path = project.path().join(LOCK_FILE_NAME);
+        lockfile.write(&path).unwrap();
+        assert_eq!(find(&nested), Some(path.clone()));
+
+        let read = Lockfile::read(&path).unwrap();
+        assert_eq!(read, lockfile);
+        assert_eq!(read.build(Platform::Linux).unwrap(), build);
+        assert!(read.build(Platform::Macos).is_err());
+
+        std::fs::write(&path, "version = 1").unwrap();
+        assert!(Lockfile::read(&path).is_err());
+    }
+}
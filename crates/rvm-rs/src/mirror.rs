@@ -0,0 +1,348 @@
+use std::{
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
+use semver::Version;
+use url::Url;
+
+use crate::{
+    constants::Platform,
+    errors::Error,
+    releases::{sha256_reader, Build, Releases},
+    spec::{Channel, VersionSpec},
+};
+
+/// Selection of official builds to mirror.
+#[derive(Clone, Debug, Default)]
+pub struct MirrorOptions {
+    /// Versions to mirror, every version is mirrored when empty.
+    /// Requirements select every matching version, `latest` only the newest one.
+    pub specs: Vec<VersionSpec>,
+    /// Channels to mirror, every channel is mirrored when empty
+    pub channels: Vec<Channel>,
+    /// Platforms to mirror, only the current platform is mirrored when empty
+    pub platforms: Vec<Platform>,
+}
+
+/// Outcome of a mirror synchronization.
+#[derive(Debug, Default)]
+pub struct MirrorReport {
+    /// Builds that were downloaded into the mirror
+    pub downloaded: Vec<(Platform, Version)>,
+    /// Builds that were already present in the mirror and passed verification
+    pub verified: Vec<(Platform, Version)>,
+}
+
+/// Self-contained copy of the official releases in a local directory.
+///
+/// The directory has the layout of [`crate::REPO_URL`]: stable releases are listed in `<dir>/<platform>/list.json`,
+/// nightlies in `<dir>/nightly/<platform>/list.json` and binaries are stored in `<dir>/<platform>/<version>/<binary>`.
+/// Build URLs are relative to the lists, so the directory can be used as a release repository wherever it's moved
+/// or served from.
+pub struct Mirror {
+    dir: PathBuf,
+}
+
+impl Mirror {
+    /// Opens the mirror in `dir`, creating the directory if it doesn't exist
+    pub fn new(dir: impl AsRef<Path>) -> Result<Self, Error> {
+        fs::create_dir_all(dir.as_ref())?;
+        Ok(Self {
+            dir: fs::canonicalize(dir)?,
+        })
+    }
+
+    /// Directory of the mirror
+    pub fn path(&self) -> &Path {
+        &self.dir
+    }
+
+    /// `file://` URL of the mirror directory, usable as a release repository, e.g. `rvm registry add mirror <url>`
+    pub fn url(&self) -> Result<Url, Error> {
+        Url::from_directory_path(&self.dir).map_err(|_| Error::InvalidPath {
+            path: self.dir.clone(),
+        })
+    }
+
+    /// `file://` URL of the release list of the platform and channel
+    pub fn list_url(&self, platform: &Platform, channel: Channel) -> Result<Url, Error> {
+        platform.list_url(&self.url()?, channel == Channel::Nightly)
+    }
+
+    /// Downloads the selected builds that are missing and verifies the ones that are present
+    pub fn sync(&self, options: &MirrorOptions) -> Result<MirrorReport, Error> {
+        let mut report = MirrorReport::default();
+        let platforms = if options.platforms.is_empty() {
+            vec![Platform::get()?]
+        } else {
+            options.platforms.clone()
+        };
+
+        for platform in platforms {
+            self.sync_platform(&platform, options, &mut report)?;
+        }
+
+        Ok(report)
+    }
+
+    fn sync_platform(
+        &self,
+        platform: &Platform,
+        options: &MirrorOptions,
+        report: &mut MirrorReport,
+    ) -> Result<(), Error> {
+        let channels = if options.channels.is_empty() {
+            vec![Channel::Stable, Channel::Nightly]
+        } else {
+            options.channels.clone()
+        };
+
+        let mut upstream: Option<Releases> = None;
+        for channel in channels {
+            let mut releases = Releases::new(platform.download_url(channel == Channel::Nightly)?)?;
+            match upstream.as_mut() {
+                Some(upstream) => upstream.merge(&mut releases),
+                None => upstream = Some(releases),
+            }
+        }
+        let upstream = upstream.expect("at least one channel is mirrored");
+        self.sync_releases(platform, &upstream, options, report)
+    }
+
+    fn sync_releases(
+        &self,
+        platform: &Platform,
+        upstream: &Releases,
+        options: &MirrorOptions,
+        report: &mut MirrorReport,
+    ) -> Result<(), Error> {
+        let versions: Vec<&Version> = upstream.builds.iter().map(|build| &build.version).collect();
+        let selected = |version: &Version| {
+            options.specs.is_empty()
+                || options.specs.iter().any(|spec| match spec {
+                    VersionSpec::Latest(_) => {
+                        spec.select(versions.iter().copied()) == Some(version)
+                    }
+                    spec => spec.matches(version),
+                })
+        };
+
+        let platform_dir = self.dir.join(platform.name());
+        let mut builds = Vec::new();
+        for build in upstream
+            .builds
+            .iter()
+            .filter(|build| selected(&build.version))
+        {
+            let folder = platform_dir.join(build.version.to_string());
+            let path = folder.join(&build.name);
+
+            let present = match fs::File::open(&path) {
+                Ok(mut binary) => sha256_reader(&mut binary)?.eq_ignore_ascii_case(&build.sha256),
+                Err(err) if err.kind() == ErrorKind::NotFound => false,
+                Err(err) => return Err(err.into()),
+            };
+            if present {
+                report.verified.push((*platform, build.version.clone()));
+            } else {
+                let binary = build.download_binary()?;
+                fs::create_dir_all(&folder)?;
+                let partial = folder.join(format!("{}.part", build.name));
+                fs::write(&partial, &binary)?;
+                fs::rename(&partial, &path)?;
+                report.downloaded.push((*platform, build.version.clone()));
+            }
+
+            let mut build = build.clone();
+            build.url = Url::from_file_path(&path).map_err(|_| Error::InvalidPath { path })?;
+            builds.push(build);
+        }
+
+        // Builds mirrored by previous runs stay available as long as their binaries are present.
+        // Lists of older mirrors have nightlies next to stable releases, they are moved to the nightly list.
+        let mut previous = Vec::new();
+        for channel in [Channel::Stable, Channel::Nightly] {
+            if let Ok(releases) = Releases::new(self.list_url(platform, channel)?) {
+                previous.extend(releases.builds);
+            }
+        }
+        for build in previous {
+            let present = build
+                .url
+                .to_file_path()
+                .is_ok_and(|path| path.starts_with(&platform_dir) && path.exists());
+            if present && !builds.iter().any(|other| other.version == build.version) {
+                builds.push(build);
+            }
+        }
+        builds.sort_by(|a, b| a.version.cmp(&b.version));
+
+        for channel in [Channel::Stable, Channel::Nightly] {
+            let channel_builds = builds
+                .iter()
+                .filter(|build| Channel::of(&build.version) == channel)
+                .cloned()
+                .collect();
+            self.write_list(&self.list_url(platform, channel)?, channel_builds)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the release list with build URLs relative to the list
+    fn write_list(&self, list_url: &Url, builds: Vec<Build>) -> Result<(), Error> {
+        let mut list = serde_json::to_value(Releases::from_builds(builds))?;
+        for build in list
+            .get_mut("builds")
+            .and_then(serde_json::Value::as_array_mut)
+            .into_iter()
+            .flatten()
+        {
+            let Some(build_url) = build.get_mut("url") else {
+                continue;
+            };
+            let relative = build_url
+                .as_str()
+                .and_then(|url| Url::parse(url).ok())
+                .and_then(|url| list_url.make_relative(&url));
+            if let Some(relative) = relative {
+                *build_url = relative.into();
+            }
+        }
+
+        let path = list_url.to_file_path().map_err(|_| Error::InvalidFileUrl {
+            url: list_url.clone(),
+        })?;
+        fs::create_dir_all(path.parent().expect("lists are in the mirror directory"))?;
+        let file = fs::File::create(path)?;
+        serde_json::to_writer_pretty(file, &list).map_err(Into::into)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::fs;
+
+    use semver::Version;
+    use url::Url;
+
+    use super::{Mirror, MirrorOptions, MirrorReport};
+    use crate::{
+        constants::Platform,
+        releases::{sha256, Build, Releases},
+        spec::Channel,
+    };
+
+    fn upstream(dir: &std::path::Path, versions: &[&str]) -> Releases {
+        let builds = versions
+            .iter()
+            .map(|version| {
+                let binary = format!("#!/bin/sh\necho {version}\n");
+                let path = dir.join(format!("resolc-{version}"));
+                fs::write(&path, &binary).unwrap();
+                serde_json::from_value::<Build>(serde_json::json!({
+                    "name": "resolc",
+                    "version": version,
+                    "longVersion": version,
+                    "url": Url::from_file_path(&path).unwrap(),
+                    "sha256": sha256(binary.as_bytes()),
+                    "firstSolcVersion": "0.8.0",
+                    "lastSolcVersion": "0.8.30"
+                }))
+                .unwrap()
+            })
+            .collect();
+        Releases::from_builds(builds)
+    }
+
+    /// Mirrors the builds for the current platform, so the mirror can be read as a release repository
+    fn sync(mirror: &Mirror, upstream: &Releases) -> MirrorReport {
+        let mut report = MirrorReport::default();
+        mirror
+            .sync_releases(
+                &Platform::get().unwrap(),
+                upstream,
+                &MirrorOptions::default(),
+                &mut report,
+            )
+            .unwrap();
+        report
+    }
+
+    #[test]
+    fn initial_sync() {
+        let platform = Platform::get().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let releases = upstream(dir.path(), &["0.3.0", "0.4.0", "0.5.0-nightly.1"]);
+        let mirror = Mirror::new(dir.path().join("mirror")).unwrap();
+
+        let report = sync(&mirror, &releases);
+        assert_eq!(report.downloaded.len(), 3);
+        assert!(report.verified.is_empty());
+
+        // Channels are listed separately, like in the official release repository.
+        let list =
+            fs::read_to_string(mirror.path().join(platform.name()).join("list.json")).unwrap();
+        assert!(list.contains(r#""url": "0.3.0/resolc""#));
+        assert!(!list.contains("nightly"));
+        let stable = Releases::new(mirror.list_url(&platform, Channel::Stable).unwrap()).unwrap();
+        let nightly = Releases::new(mirror.list_url(&platform, Channel::Nightly).unwrap()).unwrap();
+        assert_eq!((stable.builds.len(), nightly.builds.len()), (2, 1));
+
+        // The lists don't depend on where the mirror is, it keeps working once the directory is moved.
+        fs::rename(mirror.path(), dir.path().join("moved")).unwrap();
+        let moved = Mirror::new(dir.path().join("moved")).unwrap();
+        let mirrored = Releases::new(moved.url().unwrap()).unwrap();
+        assert_eq!(mirrored.builds.len(), 3);
+        let build = mirrored.get_build(&Version::new(0, 4, 0)).unwrap();
+        assert_eq!(
+            build.url.to_file_path().unwrap(),
+            moved
+                .path()
+                .join(platform.name())
+                .join("0.4.0")
+                .join("resolc")
+        );
+        assert_eq!(build.download_binary().unwrap(), b"#!/bin/sh\necho 0.4.0\n");
+        let build = mirrored
+            .get_build(&Version::parse("0.5.0-nightly.1").unwrap())
+            .unwrap();
+        assert_eq!(
+            build.download_binary().unwrap(),
+            b"#!/bin/sh\necho 0.5.0-nightly.1\n"
+        );
+    }
+
+    #[test]
+    fn incremental_sync() {
+        let platform = Platform::get().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let mirror = Mirror::new(dir.path().join("mirror")).unwrap();
+        sync(&mirror, &upstream(dir.path(), &["0.3.0"]));
+
+        // Present builds are verified by their sha256, a damaged one is downloaded again.
+        let damaged = mirror
+            .path()
+            .join(platform.name())
+            .join("0.3.0")
+            .join("resolc");
+        fs::write(&damaged, b"damaged").unwrap();
+        let report = sync(&mirror, &upstream(dir.path(), &["0.3.0", "0.4.0"]));
+        assert_eq!(
+            report.downloaded,
+            [
+                (platform, Version::new(0, 3, 0)),
+                (platform, Version::new(0, 4, 0))
+            ]
+        );
+        assert_eq!(fs::read(&damaged).unwrap(), b"#!/bin/sh\necho 0.3.0\n");
+
+        // Builds that are no longer upstream stay in the list while their binaries are present.
+        let report = sync(&mirror, &upstream(dir.path(), &["0.4.0"]));
+        assert_eq!(report.verified, [(platform, Version::new(0, 4, 0))]);
+        assert!(report.downloaded.is_empty());
+        let mirrored = Releases::new(mirror.list_url(&platform, Channel::Stable).unwrap()).unwrap();
+        assert_eq!(mirrored.builds.len(), 2);
+    }
+}
@@ -0,0 +1,790 @@
+use std::{cell::RefCell, collections::BTreeMap, time::Duration};
+
+use reqwest::{
+    blocking::{Client, RequestBuilder, Response},
+    header, StatusCode,
+};
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::{
+    constants::Platform,
+    errors::Error,
+    registry::SkippedBuild,
+    releases::{sha256, Build, Releases},
+};
+
+const MANIFEST_MEDIA_TYPE: &str = "application/vnd.oci.image.manifest.v1+json";
+const BUILD_MEDIA_TYPE: &str = "application/vnd.parity.resolc.build.v1+json";
+const BINARY_MEDIA_TYPE: &str = "application/vnd.parity.resolc.binary.v1";
+const TITLE_ANNOTATION: &str = "org.opencontainers.image.title";
+
+/// Environment variable with the user name exchanged for OCI registry tokens together with [`OCI_PASSWORD_ENV`]
+pub const OCI_USERNAME_ENV: &str = "RVM_OCI_USERNAME";
+/// Environment variable with the password or access token exchanged for OCI registry tokens
+pub const OCI_PASSWORD_ENV: &str = "RVM_OCI_PASSWORD";
+
+/// Resolc binaries stored in an OCI registry.
+///
+/// `oci://<host>/<repository>` is mapped to the `<repository>/<platform>` repository of `https://<host>`,
+/// `oci+http://` can be used for registries without TLS.
+///
+/// Every tag is a Resolc version and points to a manifest with the `build.json` metadata as config
+/// and the binary as the only layer. The layer digest has to match the `sha256` of the build.
+pub(crate) struct OciRepository {
+    base: Url,
+    repository: String,
+    client: Client,
+    auth: Auth,
+}
+
+/// Authorization of registry requests, negotiated on the `WWW-Authenticate` challenge of a `401` response.
+///
+/// Bearer challenges are answered with a token from the realm of the challenge, requested with the credentials
+/// if there are any and anonymously otherwise. Basic challenges are answered with the credentials.
+#[derive(Debug, Default)]
+pub(crate) struct Auth {
+    credentials: Option<(String, String)>,
+    authorization: RefCell<Option<Authorization>>,
+}
+
+#[derive(Clone, Debug)]
+enum Authorization {
+    Basic,
+    Bearer(String),
+}
+
+#[derive(Deserialize)]
+struct Token {
+    #[serde(default)]
+    token: Option<String>,
+    #[serde(default)]
+    access_token: Option<String>,
+}
+
+impl Auth {
+    /// Uses the credentials of [`OCI_USERNAME_ENV`] and [`OCI_PASSWORD_ENV`] when both are set
+    pub(crate) fn from_env() -> Self {
+        let credentials = std::env::var(OCI_USERNAME_ENV)
+            .ok()
+            .zip(std::env::var(OCI_PASSWORD_ENV).ok());
+        Self {
+            credentials,
+            authorization: RefCell::default(),
+        }
+    }
+
+    /// Sends the request, answering an authentication challenge and sending it again once
+    pub(crate) fn send(&self, client: &Client, request: RequestBuilder) -> Result<Response, Error> {
+        let retry = request.try_clone();
+        let response = self.authorize(request).send()?;
+        if response.status() != StatusCode::UNAUTHORIZED {
+            return Ok(response);
+        }
+        let (Some(retry), Some(challenge)) = (
+            retry,
+            response
+                .headers()
+                .get(header::WWW_AUTHENTICATE)
+                .and_then(|challenge| challenge.to_str().ok()),
+        ) else {
+            return Ok(response);
+        };
+
+        let (scheme, params) = challenge.split_once(' ').unwrap_or((challenge, ""));
+        let authorization = if scheme.eq_ignore_ascii_case("bearer") {
+            Authorization::Bearer(self.token(client, response.url(), &parse_params(params))?)
+        } else if scheme.eq_ignore_ascii_case("basic") && self.credentials.is_some() {
+            Authorization::Basic
+        } else {
+            return Ok(response);
+        };
+        self.authorization.replace(Some(authorization));
+        Ok(self.authorize(retry).send()?)
+    }
+
+    fn authorize(&self, request: RequestBuilder) -> RequestBuilder {
+        match (&*self.authorization.borrow(), &self.credentials) {
+            (Some(Authorization::Bearer(token)), _) => request.bearer_auth(token),
+            (Some(Authorization::Basic), Some((username, password))) => {
+                request.basic_auth(username, Some(password))
+            }
+            _ => request,
+        }
+    }
+
+    /// Requests a token from the realm of a Bearer challenge for its service and scope
+    ///
+    /// The realm has to be on the host of the challenged request or use https, credentials aren't sent anywhere else.
+    fn token(
+        &self,
+        client: &Client,
+        challenged: &Url,
+        params: &[(String, String)],
+    ) -> Result<String, Error> {
+        let unauthorized = |reason: String| Error::OciAuthentication { reason };
+        let realm = params
+            .iter()
+            .find(|(key, _)| key == "realm")
+            .ok_or_else(|| unauthorized("the challenge has no realm".to_owned()))?;
+        let mut url = Url::parse(&realm.1)?;
+        if !is_trusted_realm(challenged, &url) {
+            return Err(unauthorized(format!(
+                "the token realm {url} is neither on {} nor https",
+                challenged.host_str().unwrap_or_default()
+            )));
+        }
+        for (key, value) in params.iter().filter(|(key, _)| key != "realm") {
+            url.query_pairs_mut().append_pair(key, value);
+        }
+
+        let mut request = client.get(url);
+        if let Some((username, password)) = &self.credentials {
+            request = request.basic_auth(username, Some(password));
+        }
+        let response = request.send()?;
+        if !response.status().is_success() {
+            return Err(unauthorized(format!(
+                "the token request failed with {}",
+                response.status()
+            )));
+        }
+        let token: Token = response.json()?;
+        token
+            .token
+            .or(token.access_token)
+            .ok_or_else(|| unauthorized("the token response has no token".to_owned()))
+    }
+}
+
+fn is_trusted_realm(challenged: &Url, realm: &Url) -> bool {
+    realm.scheme() == "https" || realm.origin() == challenged.origin()
+}
+
+/// Parses the `key="value"` parameters of a challenge, quoted values may contain commas
+fn parse_params(params: &str) -> Vec<(String, String)> {
+    let mut parsed = Vec::new();
+    let mut rest = params.trim();
+    while let Some((key, value)) = rest.split_once('=') {
+        let key = key
+            .trim()
+            .trim_start_matches(',')
+            .trim()
+            .to_ascii_lowercase();
+        let value = value.trim_start();
+        let (value, remaining) = match value.strip_prefix('"') {
+            Some(quoted) => {
+                let end = quoted.find('"').unwrap_or(quoted.len());
+                (&quoted[..end], quoted.get(end + 1..).unwrap_or_default())
+            }
+            None => value.split_once(',').unwrap_or((value, "")),
+        };
+        parsed.push((key, value.trim().to_owned()));
+        rest = remaining;
+    }
+    parsed
+}
+
+/// Target of the `rel="next"` link of a `Link` header, used to paginate tag lists
+fn next_link(link: &str) -> Option<&str> {
+    link.split(',').find_map(|link| {
+        let (target, params) = link.split_once(';')?;
+        params
+            .split(';')
+            .any(|param| param.replace([' ', '"'], "") == "rel=next")
+            .then(|| target.trim().trim_start_matches('<').trim_end_matches('>'))
+    })
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Manifest {
+    schema_version: u32,
+    media_type: String,
+    config: Descriptor,
+    layers: Vec<Descriptor>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Descriptor {
+    media_type: String,
+    digest: String,
+    size: usize,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    annotations: BTreeMap<String, String>,
+}
+
+impl Descriptor {
+    fn new(media_type: &str, blob: &[u8]) -> Self {
+        Self {
+            media_type: media_type.to_owned(),
+            digest: digest(blob),
+            size: blob.len(),
+            annotations: BTreeMap::new(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct Tags {
+    #[serde(default)]
+    tags: Option<Vec<String>>,
+}
+
+fn digest(blob: &[u8]) -> String {
+    format!("sha256:{}", sha256(blob))
+}
+
+impl OciRepository {
+    /// Checks whether the URL points to an OCI registry
+    pub(crate) fn is_oci(url: &Url) -> bool {
+        matches!(url.scheme(), "oci" | "oci+http" | "oci+https")
+    }
+
+    pub(crate) fn from_url(url: &Url, platform: &Platform) -> Result<Self, Error> {
+        let scheme = match url.scheme() {
+            "oci" | "oci+https" => "https",
+            "oci+http" => "http",
+            _ => {
+                return Err(Error::InvalidOciUrl {
+                    url: url.to_string(),
+                })
+            }
+        };
+        let host = url.host_str().ok_or_else(|| Error::InvalidOciUrl {
+            url: url.to_string(),
+        })?;
+        let mut base = Url::parse(&format!("{scheme}://{host}"))?;
+        base.set_port(url.port())
+            .expect("http(s) URLs can have a port");
+        let repository = url.path().trim_matches('/');
+        if repository.is_empty() {
+            return Err(Error::InvalidOciUrl {
+                url: url.to_string(),
+            });
+        }
+
+        Ok(Self {
+            base,
+            repository: format!("{repository}/{}", platform.name()),
+            client: Client::builder()
+                .timeout(Duration::from_secs(300))
+                .build()?,
+            auth: Auth::from_env(),
+        })
+    }
+
+    fn send(&self, request: RequestBuilder) -> Result<Response, Error> {
+        self.auth.send(&self.client, request)
+    }
+
+    fn endpoint(&self, path: &str) -> Result<Url, Error> {
+        self.base
+            .join(&format!("/v2/{}/{path}", self.repository))
+            .map_err(Into::into)
+    }
+
+    /// Lists the tags of every page, following the `Link` headers of the responses
+    fn tags(&self) -> Result<Vec<String>, Error> {
+        let mut tags = Vec::new();
+        let mut url = Some(self.endpoint("tags/list")?);
+        while let Some(page) = url.take() {
+            let response = self.send(self.client.get(page))?.error_for_status()?;
+            if let Some(link) = response
+                .headers()
+                .get(header::LINK)
+                .and_then(|link| link.to_str().ok())
+                .and_then(next_link)
+            {
+                url = Some(self.base.join(link)?);
+            }
+            let page: Tags = response.json()?;
+            tags.extend(page.tags.unwrap_or_default());
+        }
+        Ok(tags)
+    }
+
+    fn manifest(&self, tag: &str) -> Result<Manifest, Error> {
+        self.send(
+            self.client
+                .get(self.endpoint(&format!("manifests/{tag}"))?)
+                .header(header::ACCEPT, MANIFEST_MEDIA_TYPE),
+        )?
+        .error_for_status()?
+        .json()
+        .map_err(Into::into)
+    }
+
+    fn blob(&self, digest: &str) -> Result<Vec<u8>, Error> {
+        Ok(self
+            .send(self.client.get(self.endpoint(&format!("blobs/{digest}"))?))?
+            .error_for_status()?
+            .bytes()?
+            .to_vec())
+    }
+
+    /// Downloads the binary of a build of the repository, the registry credentials are only offered to its host
+    pub(crate) fn download(&self, build: &Build) -> Result<Vec<u8>, Error> {
+        if build.url.origin() != self.base.origin() {
+            return build.download_binary();
+        }
+        let binary = self
+            .send(self.client.get(build.url.clone()))?
+            .error_for_status()?
+            .bytes()?;
+        build.verify_binary(&binary)?;
+        Ok(binary.to_vec())
+    }
+
+    /// Reads the build published under `tag`, its URL points to the binary blob.
+    fn build(&self, tag: &str) -> Result<Build, Error> {
+        let manifest = self.manifest(tag)?;
+        if manifest.config.media_type != BUILD_MEDIA_TYPE {
+            return Err(Error::InvalidOciArtifact {
+                reference: format!("{}:{tag}", self.repository),
+                reason: format!(
+                    "unexpected config media type {}",
+                    manifest.config.media_type
+                ),
+            });
+        }
+        let layer = manifest
+            .layers
+            .iter()
+            .find(|layer| layer.media_type == BINARY_MEDIA_TYPE)
+            .ok_or_else(|| Error::InvalidOciArtifact {
+                reference: format!("{}:{tag}", self.repository),
+                reason: "no binary layer".to_owned(),
+            })?;
+
+        let config = self.blob(&manifest.config.digest)?;
+        let mut build: Build = serde_json::from_slice(&config)?;
+        let layer_digest = format!("sha256:{}", build.sha256);
+        if layer.digest != layer_digest {
+            return Err(Error::ChecksumValidationError {
+                expected: layer_digest,
+                actual: layer.digest.clone(),
+            });
+        }
+        build.url = self.endpoint(&format!("blobs/{}", layer.digest))?;
+        build.registry = None;
+
+        Ok(build)
+    }
+
+    /// Lists every tag that is a valid Resolc version as a release
+    ///
+    /// Other tags, e.g. `latest` or signatures, are ignored. Version tags without a valid Resolc artifact are left
+    /// out and reported in [`Releases::skipped`], so one bad tag doesn't make the whole registry unavailable.
+    pub(crate) fn releases(&self) -> Result<Releases, Error> {
+        let mut builds = Vec::new();
+        let mut skipped = Vec::new();
+        for tag in self.tags()? {
+            if Version::parse(&tag).is_err() {
+                continue;
+            }
+            match self.build(&tag) {
+                Ok(build) => builds.push(build),
+                Err(err) => skipped.push(SkippedBuild {
+                    registry: None,
+                    reference: format!("{}:{tag}", self.repository),
+                    reason: err.to_string(),
+                }),
+            }
+        }
+
+        let mut releases = Releases::from_builds(builds);
+        releases.skipped = skipped;
+        Ok(releases)
+    }
+
+    fn upload_blob(&self, blob: &[u8]) -> Result<(), Error> {
+        let digest = digest(blob);
+        let exists = self.send(self.client.head(self.endpoint(&format!("blobs/{digest}"))?))?;
+        if exists.status() == StatusCode::OK {
+            return Ok(());
+        }
+
+        let upload = self
+            .send(self.client.post(self.endpoint("blobs/uploads/")?))?
+            .error_for_status()?;
+        let location = upload
+            .headers()
+            .get(header::LOCATION)
+            .and_then(|location| location.to_str().ok())
+            .ok_or_else(|| Error::InvalidOciArtifact {
+                reference: self.repository.clone(),
+                reason: "blob upload has no location".to_owned(),
+            })?;
+        let mut location = self.base.join(location)?;
+        location.query_pairs_mut().append_pair("digest", &digest);
+
+        self.send(
+            self.client
+                .put(location)
+                .header(header::CONTENT_TYPE, "application/octet-stream")
+                .body(blob.to_vec()),
+        )?
+        .error_for_status()?;
+        Ok(())
+    }
+
+    /// Publishes the binary under a tag named after its version
+    pub(crate) fn push(&self, build: &Build, binary: &[u8]) -> Result<(), Error> {
+        build.verify_binary(binary)?;
+
+        let mut build = build.clone();
+        build.registry = None;
+        let config = serde_json::to_vec(&build)?;
+
+        self.upload_blob(binary)?;
+        self.upload_blob(&config)?;
+
+        let mut layer = Descriptor::new(BINARY_MEDIA_TYPE, binary);
+        layer
+            .annotations
+            .insert(TITLE_ANNOTATION.to_owned(), build.name.clone());
+        let manifest = Manifest {
+            schema_version: 2,
+            media_type: MANIFEST_MEDIA_TYPE.to_owned(),
+            config: Descriptor::new(BUILD_MEDIA_TYPE, &config),
+            layers: vec![layer],
+        };
+
+        self.send(
+            self.client
+                .put(self.endpoint(&format!("manifests/{}", build.version))?)
+                .header(header::CONTENT_TYPE, MANIFEST_MEDIA_TYPE)
+                .body(serde_json::to_vec(&manifest)?),
+        )?
+        .error_for_status()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        collections::BTreeMap,
+        sync::{mpsc, Arc, Mutex},
+    };
+
+    use semver::Version;
+    use tiny_http::{Header, Method, Request, Response, Server};
+    use url::Url;
+
+    use super::{digest, is_trusted_realm, next_link, parse_params, Auth, OciRepository};
+    use crate::{constants::Platform, errors::Error, releases::Build};
+
+    const TOKEN: &str = "token-1";
+    /// `rvm:secret`
+    const CREDENTIALS: &str = "Basic cnZtOnNlY3JldA==";
+
+    /// Authentication required by the fake registry
+    #[derive(Clone, Copy, PartialEq, Eq)]
+    enum Access {
+        Open,
+        AnonymousToken,
+        CredentialsToken,
+    }
+
+    #[derive(Default)]
+    struct Storage {
+        blobs: BTreeMap<String, Vec<u8>>,
+        manifests: BTreeMap<String, Vec<u8>>,
+    }
+
+    fn header<'a>(request: &'a Request, name: &'static str) -> Option<&'a str> {
+        request
+            .headers()
+            .iter()
+            .find(|header| header.field.equiv(name))
+            .map(|header| header.value.as_str())
+    }
+
+    /// Minimal in-memory stand-in for the OCI distribution API, returns the registry address.
+    ///
+    /// Tags are listed one per page, protected registries answer with a Bearer challenge for the `/token` realm.
+    fn local_registry(access: Access) -> String {
+        let server = Server::http("127.0.0.1:0").unwrap();
+        let address = server.server_addr().to_ip().unwrap().to_string();
+        let storage = Arc::new(Mutex::new(Storage::default()));
+        let realm = format!("http://{address}/token");
+
+        std::thread::spawn(move || {
+            for mut request in server.incoming_requests() {
+                let mut body = Vec::new();
+                request.as_reader().read_to_end(&mut body).unwrap();
+                let url = Url::parse(&format!("http://localhost{}", request.url())).unwrap();
+                let path = url.path().to_owned();
+                let mut storage = storage.lock().unwrap();
+                let authorized = access == Access::Open
+                    || header(&request, "Authorization") == Some(&format!("Bearer {TOKEN}"));
+
+                let response = if path == "/token" {
+                    if access == Access::CredentialsToken
+                        && header(&request, "Authorization") != Some(CREDENTIALS)
+                    {
+                        Response::from_data(vec![]).with_status_code(401)
+                    } else {
+                        let scope = url.query_pairs().find(|(key, _)| key == "scope").unwrap();
+                        assert_eq!(scope.1, "repository:resolc/linux:pull,push");
+                        Response::from_string(serde_json::json!({ "token": TOKEN }).to_string())
+                    }
+                } else if !authorized {
+                    Response::from_data(vec![]).with_status_code(401).with_header(
+                        Header::from_bytes(
+                            "WWW-Authenticate",
+                            format!(
+                                r#"Bearer realm="{realm}",service="fake",scope="repository:resolc/linux:pull,push""#
+                            ),
+                        )
+                        .unwrap(),
+                    )
+                } else if path.ends_with("/tags/list") {
+                    let last = url.query_pairs().find(|(key, _)| key == "last");
+                    let mut tags = storage.manifests.keys().filter(|tag| {
+                        last.as_ref()
+                            .is_none_or(|(_, last)| tag.as_str() > last.as_ref())
+                    });
+                    let page: Vec<&String> = tags.next().into_iter().collect();
+                    let response =
+                        Response::from_string(serde_json::json!({ "tags": page }).to_string());
+                    match (page.first(), tags.next()) {
+                        (Some(last), Some(_)) => response.with_header(
+                            Header::from_bytes(
+                                "Link",
+                                format!(r#"<{path}?n=1&last={last}>; rel="next""#),
+                            )
+                            .unwrap(),
+                        ),
+                        _ => response,
+                    }
+                } else if let Some((_, tag)) = path.split_once("/manifests/") {
+                    match request.method() {
+                        Method::Put => {
+                            storage.manifests.insert(tag.to_owned(), body);
+                            Response::from_data(vec![]).with_status_code(201)
+                        }
+                        _ => match storage.manifests.get(tag) {
+                            Some(manifest) => Response::from_data(manifest.clone()),
+                            None => Response::from_data(vec![]).with_status_code(404),
+                        },
+                    }
+                } else if path.ends_with("/blobs/uploads/") {
+                    Response::from_data(vec![])
+                        .with_status_code(202)
+                        .with_header(
+                            Header::from_bytes("Location", "/v2/resolc/linux/blobs/uploads/1")
+                                .unwrap(),
+                        )
+                } else if path.contains("/blobs/uploads/") {
+                    let (_, digest) = url.query_pairs().find(|(key, _)| key == "digest").unwrap();
+                    storage.blobs.insert(digest.into_owned(), body);
+                    Response::from_data(vec![]).with_status_code(201)
+                } else if let Some((_, digest)) = path.split_once("/blobs/") {
+                    match storage.blobs.get(digest) {
+                        Some(blob) => Response::from_data(blob.clone()),
+                        None => Response::from_data(vec![]).with_status_code(404),
+                    }
+                } else {
+                    Response::from_data(vec![]).with_status_code(404)
+                };
+                let _ = request.respond(response);
+            }
+        });
+
+        address
+    }
+
+    fn repository(address: &str, credentials: Option<(&str, &str)>) -> OciRepository {
+        let url = Url::parse(&format!("oci+http://{address}/resolc")).unwrap();
+        let mut repository = OciRepository::from_url(&url, &Platform::Linux).unwrap();
+        repository.auth = Auth {
+            credentials: credentials
+                .map(|(username, password)| (username.to_owned(), password.to_owned())),
+            ..Auth::default()
+        };
+        repository
+    }
+
+    fn build(version: &str, binary: &[u8]) -> Build {
+        serde_json::from_value(serde_json::json!({
+            "name": "resolc-x86_64-unknown-linux-musl",
+            "version": version,
+            "longVersion": format!("{version}+commit.0000000"),
+            "url": "https://example.com/resolc",
+            "sha256": digest(binary).trim_start_matches("sha256:"),
+            "firstSolcVersion": "0.8.0",
+            "lastSolcVersion": "0.8.30"
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn push_and_pull() {
+        let address = local_registry(Access::Open);
+        let repository = repository(&address, None);
+
+        let binary = b"#!/bin/sh\necho resolc\n";
+        repository
+            .push(&build("0.4.0-patch.1", binary), binary)
+            .unwrap();
+
+        let releases = repository.releases().unwrap();
+        let version = Version::parse("0.4.0-patch.1").unwrap();
+        let pulled = releases.get_build(&version).unwrap();
+        assert_eq!(releases.latest_release, version);
+        assert_eq!(
+            pulled.url.as_str(),
+            format!("http://{address}/v2/resolc/linux/blobs/{}", digest(binary))
+        );
+        assert_eq!(pulled.download_binary().unwrap(), binary);
+    }
+
+    #[test]
+    fn token_auth_and_pagination() {
+        let address = local_registry(Access::AnonymousToken);
+        let repository = repository(&address, None);
+
+        let (old, new) = (b"#!/bin/sh\necho 0.3.0\n", b"#!/bin/sh\necho 0.4.0\n");
+        repository.push(&build("0.3.0", old), old).unwrap();
+        repository.push(&build("0.4.0", new), new).unwrap();
+
+        // Every tag is on its own page, both are listed by following the `Link` headers.
+        let releases = repository.releases().unwrap();
+        assert_eq!(releases.builds.len(), 2);
+        assert_eq!(releases.latest_release, Version::new(0, 4, 0));
+        // Blob downloads answer the challenge with an anonymous token as well.
+        let pulled = releases.get_build(&Version::new(0, 3, 0)).unwrap();
+        assert_eq!(repository.download(pulled).unwrap(), old);
+    }
+
+    #[test]
+    fn credentials_stay_on_registry_host() {
+        let address = local_registry(Access::CredentialsToken);
+        let repository = repository(&address, Some(("rvm", "secret")));
+
+        // Another host that asks for credentials on its own realm, as a hostile release list could point to.
+        let server = Server::http("127.0.0.1:0").unwrap();
+        let other = server.server_addr().to_ip().unwrap().to_string();
+        let (sender, receiver) = mpsc::channel();
+        let realm = format!("http://{other}/token");
+        std::thread::spawn(move || {
+            for request in server.incoming_requests() {
+                let authorization = header(&request, "Authorization").map(ToOwned::to_owned);
+                sender
+                    .send((request.url().to_owned(), authorization))
+                    .unwrap();
+                let challenge = format!(r#"Bearer realm="{realm}",service="other""#);
+                let _ = request.respond(
+                    Response::from_data(vec![])
+                        .with_status_code(401)
+                        .with_header(Header::from_bytes("WWW-Authenticate", challenge).unwrap()),
+                );
+            }
+        });
+
+        let binary = b"#!/bin/sh\necho resolc\n";
+        let mut build = build("0.4.0", binary);
+        build.url = Url::parse(&format!("http://{other}/resolc")).unwrap();
+        assert!(build.download_binary().is_err());
+        assert!(repository.download(&build).is_err());
+        let requests: Vec<_> = receiver.try_iter().collect();
+        assert_eq!(
+            requests,
+            [("/resolc".to_owned(), None), ("/resolc".to_owned(), None)]
+        );
+    }
+
+    #[test]
+    fn invalid_tags_are_skipped() {
+        let address = local_registry(Access::Open);
+        let repository = repository(&address, None);
+        let binary = b"#!/bin/sh\necho resolc\n";
+        repository.push(&build("0.4.0", binary), binary).unwrap();
+        for tag in ["0.3.0", "latest"] {
+            reqwest::blocking::Client::new()
+                .put(format!("http://{address}/v2/resolc/linux/manifests/{tag}"))
+                .body("{}")
+                .send()
+                .unwrap();
+        }
+
+        let releases = repository.releases().unwrap();
+        assert_eq!(releases.latest_release, Version::new(0, 4, 0));
+        assert_eq!(releases.builds.len(), 1);
+        assert_eq!(releases.skipped.len(), 1);
+        assert_eq!(releases.skipped[0].reference, "resolc/linux:0.3.0");
+    }
+
+    #[test]
+    fn token_auth_with_credentials() {
+        let address = local_registry(Access::CredentialsToken);
+        let binary = b"#!/bin/sh\necho resolc\n";
+
+        let anonymous = repository(&address, None);
+        assert!(matches!(
+            anonymous.push(&build("0.4.0", binary), binary),
+            Err(Error::OciAuthentication { .. })
+        ));
+
+        let authenticated = repository(&address, Some(("rvm", "secret")));
+        authenticated.push(&build("0.4.0", binary), binary).unwrap();
+        assert_eq!(authenticated.releases().unwrap().builds.len(), 1);
+    }
+
+    #[test]
+    fn push_rejects_mismatching_digest() {
+        let address = local_registry(Access::Open);
+        let repository = repository(&address, None);
+
+        let binary = b"#!/bin/sh\necho resolc\n";
+        let result = repository.push(&build("0.4.0-patch.1", binary), b"tampered");
+        assert!(result.is_err());
+        assert!(repository.releases().unwrap().builds.is_empty());
+    }
+
+    #[test]
+    fn headers() {
+        assert_eq!(
+            parse_params(
+                r#"realm="https://ghcr.io/token",service="ghcr.io",scope="repository:org/resolc:pull,push""#
+            ),
+            [
+                ("realm".to_owned(), "https://ghcr.io/token".to_owned()),
+                ("service".to_owned(), "ghcr.io".to_owned()),
+                (
+                    "scope".to_owned(),
+                    "repository:org/resolc:pull,push".to_owned()
+                ),
+            ]
+        );
+        assert_eq!(
+            next_link(r#"</v2/resolc/linux/tags/list?n=100&last=0.4.0>; rel="next""#),
+            Some("/v2/resolc/linux/tags/list?n=100&last=0.4.0")
+        );
+        assert_eq!(next_link(r#"</v2/a>; rel="prev""#), None);
+
+        let challenged = Url::parse("http://127.0.0.1:5000/v2/resolc/linux/tags/list").unwrap();
+        let realm = |realm: &str| is_trusted_realm(&challenged, &Url::parse(realm).unwrap());
+        assert!(realm("http://127.0.0.1:5000/token"));
+        assert!(realm("https://auth.docker.io/token"));
+        assert!(!realm("http://attacker.example.com/token"));
+    }
+
+    #[test]
+    fn repository_url() {
+        let url = Url::parse("oci://harbor.example.com:8443/compilers/resolc").unwrap();
+        let repository = OciRepository::from_url(&url, &Platform::Macos).unwrap();
+        assert_eq!(
+            repository.endpoint("tags/list").unwrap().as_str(),
+            "https://harbor.example.com:8443/v2/compilers/resolc/macos/tags/list"
+        );
+
+        let url = Url::parse("https://harbor.example.com/compilers/resolc").unwrap();
+        assert!(OciRepository::from_url(&url, &Platform::Macos).is_err());
+    }
+}
@@ -0,0 +1,336 @@
+use std::{
+    fmt::Display,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use semver::{Version, VersionReq};
+use serde::Serialize;
+
+use crate::{constants::MIN_VERSION, errors::Error, releases::Build};
+
+/// Environment variable that makes the `resolc` wrapper pick the version from the pragmas of the compiled sources,
+/// `1` or `true`.
+pub const RESOLVE_SOURCES_ENV: &str = "RVM_RESOLVE_SOURCES";
+
+/// `pragma solidity` constraint of a source file.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Pragma {
+    /// Path to the source file
+    pub path: PathBuf,
+    /// Constraint as written in the source, e.g. `>=0.8.0 <0.9.0`
+    pub constraint: String,
+}
+
+/// Newest Resolc and `solc` pair that compiles a source tree, see [`crate::VersionManager::resolve_sources`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct SourcesResolution {
+    /// Constraints of the sources
+    pub pragmas: Vec<Pragma>,
+    /// Newest Resolc version supporting `solc`
+    pub resolc: Version,
+    /// Newest `solc` version satisfying every constraint
+    pub solc: Version,
+    /// Whether the Resolc version is installed
+    pub installed: bool,
+}
+
+/// Solidity version constraint, alternatives separated by `||` of comparators separated by spaces.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct Constraint(Vec<VersionReq>);
+
+impl Constraint {
+    pub(crate) fn matches(&self, version: &Version) -> bool {
+        self.0.iter().any(|req| req.matches(version))
+    }
+}
+
+impl FromStr for Constraint {
+    type Err = ();
+
+    /// Parses the Solidity syntax, which differs from Cargo's: a bare version is exact, comparators are separated
+    /// by spaces and `a - b` is an inclusive range
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split("||")
+            .map(|alternative| {
+                let mut comparators = Vec::new();
+                let mut tokens = alternative.split_whitespace().peekable();
+                while let Some(mut token) = tokens.next() {
+                    let joined;
+                    // Operators may be separated from the version, e.g. `>= 0.8.0`.
+                    if token
+                        .chars()
+                        .all(|c| matches!(c, '<' | '>' | '=' | '^' | '~'))
+                    {
+                        joined = format!("{token}{}", tokens.next().ok_or(())?);
+                        token = &joined;
+                    }
+                    if tokens.peek() == Some(&"-") {
+                        tokens.next();
+                        comparators.push(format!(">={token}"));
+                        comparators.push(format!("<={}", tokens.next().ok_or(())?));
+                    } else if token.starts_with(|c: char| c.is_ascii_digit()) {
+                        comparators.push(format!("={token}"));
+                    } else {
+                        comparators.push(token.to_owned());
+                    }
+                }
+                if comparators.is_empty() {
+                    return Err(());
+                }
+                VersionReq::parse(&comparators.join(", ")).map_err(|_| ())
+            })
+            .collect::<Result<_, _>>()
+            .map(Constraint)
+    }
+}
+
+impl Display for Constraint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let alternatives: Vec<String> = self.0.iter().map(ToString::to_string).collect();
+        f.write_str(&alternatives.join(" || "))
+    }
+}
+
+/// Finds the `pragma solidity` constraints of the `.sol` files, directories are searched recursively
+///
+/// Hidden directories and `node_modules` are skipped.
+pub(crate) fn scan(paths: &[PathBuf]) -> Result<Vec<Pragma>, Error> {
+    let mut files = Vec::new();
+    for path in paths {
+        if path.is_dir() {
+            walk(path, &mut files)?;
+        } else {
+            files.push(path.clone());
+        }
+    }
+    files.sort();
+
+    let mut pragmas = Vec::new();
+    for path in files {
+        let source = std::fs::read_to_string(&path)?;
+        pragmas.extend(parse(&source).into_iter().map(|constraint| Pragma {
+            path: path.clone(),
+            constraint,
+        }));
+    }
+    Ok(pragmas)
+}
+
+/// Parses the constraints of the pragmas, fails on the first invalid one
+pub(crate) fn constraints(pragmas: &[Pragma]) -> Result<Vec<Constraint>, Error> {
+    pragmas
+        .iter()
+        .map(|pragma| {
+            pragma.constraint.parse().map_err(|_| Error::InvalidPragma {
+                path: pragma.path.clone(),
+                constraint: pragma.constraint.clone(),
+            })
+        })
+        .collect()
+}
+
+/// Newest `solc` version supported by the build that satisfies every constraint
+pub(crate) fn newest_solc(build: &Build, constraints: &[Constraint]) -> Option<Version> {
+    solc_versions(build).into_iter().rev().find(|version| {
+        constraints
+            .iter()
+            .all(|constraint| constraint.matches(version))
+    })
+}
+
+/// `solc` versions supported by the build, oldest first
+///
+/// Releases only name the supported range, so versions are enumerated minor by minor and patch by patch. The last
+/// patch is only known for the newest minor version, the older ones contribute their first supported release.
+fn solc_versions(build: &Build) -> Vec<Version> {
+    let first = build.first_supported_solc_version.clone().max(MIN_VERSION);
+    let last = &build.last_supported_solc_version;
+    if &first > last {
+        return Vec::new();
+    }
+    let mut versions = Vec::new();
+    for major in first.major..=last.major {
+        let first_minor = if major == first.major { first.minor } else { 0 };
+        let last_minor = if major == last.major {
+            last.minor
+        } else {
+            first_minor
+        };
+        for minor in first_minor..=last_minor {
+            let first_patch = if (major, minor) == (first.major, first.minor) {
+                first.patch
+            } else {
+                0
+            };
+            if (major, minor) == (last.major, last.minor) {
+                versions.extend(
+                    (first_patch..=last.patch).map(|patch| Version::new(major, minor, patch)),
+                );
+            } else {
+                versions.push(Version::new(major, minor, first_patch));
+            }
+        }
+    }
+    versions
+}
+
+fn walk(dir: &Path, files: &mut Vec<PathBuf>) -> Result<(), Error> {
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        if entry.file_type()?.is_dir() {
+            if !name.starts_with('.') && name != "node_modules" {
+                walk(&path, files)?;
+            }
+        } else if name.ends_with(".sol") {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Constraints of the `pragma solidity` directives of the source, comments and strings are skipped
+fn parse(source: &str) -> Vec<String> {
+    let mut code = String::with_capacity(source.len());
+    let mut chars = source.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '/' if chars.peek() == Some(&'/') => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        code.push('\n');
+                        break;
+                    }
+                }
+            }
+            '/' if chars.peek() == Some(&'*') => {
+                chars.next();
+                let mut previous = ' ';
+                for c in chars.by_ref() {
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    previous = c;
+                }
+                code.push(' ');
+            }
+            '"' | '\'' => {
+                let mut escaped = false;
+                for next in chars.by_ref() {
+                    if next == c && !escaped {
+                        break;
+                    }
+                    escaped = next == '\\' && !escaped;
+                }
+                code.push_str("\"\"");
+            }
+            c => code.push(c),
+        }
+    }
+
+    code.split([';', '{', '}'])
+        .filter_map(|statement| {
+            let mut words = statement.split_whitespace();
+            (words.next() == Some("pragma") && words.next() == Some("solidity"))
+                .then(|| words.collect::<Vec<_>>().join(" "))
+        })
+        .filter(|constraint| !constraint.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use semver::Version;
+
+    use super::{newest_solc, parse, scan, Constraint};
+    use crate::releases::Build;
+
+    #[test]
+    fn constraints() {
+        let source = r#"
+// SPDX-License-Identifier: MIT
+// pragma solidity 0.4.0;
+/* pragma solidity 0.5.0; */
+pragma solidity >=0.8.0 <0.9.0;
+pragma abicoder v2;
+contract A { string s = "pragma solidity 0.6.0;"; }
+pragma   solidity
+    ^0.8.20 || 0.7.6 ;
+"#;
+        assert_eq!(parse(source), [">=0.8.0 <0.9.0", "^0.8.20 || 0.7.6"]);
+
+        let matches = |constraint: &str, version: &str| {
+            constraint
+                .parse::<Constraint>()
+                .unwrap()
+                .matches(&Version::parse(version).unwrap())
+        };
+        assert!(matches(">=0.8.0 <0.9.0", "0.8.30"));
+        assert!(!matches(">=0.8.0 <0.9.0", "0.9.0"));
+        assert!(matches("0.8.20", "0.8.20"));
+        assert!(!matches("0.8.20", "0.8.21"));
+        assert!(matches("^0.8.20 || 0.7.6", "0.7.6"));
+        assert!(matches(">= 0.8.4", "0.8.4"));
+        assert!(matches("0.8.10 - 0.8.12", "0.8.12"));
+        assert!(!matches("0.8.10 - 0.8.12", "0.8.13"));
+        assert!("".parse::<Constraint>().is_err());
+        assert!("^0.8 ||".parse::<Constraint>().is_err());
+    }
+
+    #[test]
+    fn newest_pair() {
+        let project = tempfile::tempdir().unwrap();
+        let contracts = project.path().join("contracts");
+        std::fs::create_dir_all(contracts.join("node_modules")).unwrap();
+        std::fs::write(contracts.join("A.sol"), "pragma solidity ^0.8.0;").unwrap();
+        std::fs::write(contracts.join("B.sol"), "pragma solidity <=0.8.24;").unwrap();
+        std::fs::write(
+            contracts.join("node_modules").join("C.sol"),
+            "pragma solidity 0.4.0;",
+        )
+        .unwrap();
+        let pragmas = scan(std::slice::from_ref(&contracts)).unwrap();
+        assert_eq!(pragmas.len(), 2);
+        assert_eq!(pragmas[0].path, contracts.join("A.sol"));
+
+        let build: Build = serde_json::from_value(serde_json::json!({
+            "name": "resolc",
+            "version": "0.4.0",
+            "longVersion": "0.4.0",
+            "url": "https://example.com/resolc",
+            "sha256": "00",
+            "firstSolcVersion": "0.8.0",
+            "lastSolcVersion": "0.8.30"
+        }))
+        .unwrap();
+        let constraints = super::constraints(&pragmas).unwrap();
+        assert_eq!(
+            newest_solc(&build, &constraints),
+            Some(Version::new(0, 8, 24))
+        );
+        let old: Vec<Constraint> = vec!["<0.8.0".parse().unwrap()];
+        assert_eq!(newest_solc(&build, &old), None);
+    }
+
+    #[test]
+    fn intermediate_minors() {
+        let build: Build = serde_json::from_value(serde_json::json!({
+            "name": "resolc",
+            "version": "0.4.0",
+            "longVersion": "0.4.0",
+            "url": "https://example.com/resolc",
+            "sha256": "00",
+            "firstSolcVersion": "0.8.4",
+            "lastSolcVersion": "0.10.2"
+        }))
+        .unwrap();
+        let newest = |constraint: &str| newest_solc(&build, &[constraint.parse().unwrap()]);
+        assert_eq!(newest(">=0.8.0 <0.10.0"), Some(Version::new(0, 9, 0)));
+        assert_eq!(newest("<0.9.0"), Some(Version::new(0, 8, 4)));
+        assert_eq!(newest("^0.10.1"), Some(Version::new(0, 10, 2)));
+        assert_eq!(newest("<0.8.4"), None);
+    }
+}
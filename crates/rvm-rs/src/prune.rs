@@ -0,0 +1,180 @@
+use std::{
+    collections::BTreeSet,
+    time::{Duration, SystemTime},
+};
+
+use semver::{Version, VersionReq};
+
+use crate::spec::Channel;
+
+/// Rules selecting installed versions to remove with [`crate::VersionManager::prune`].
+///
+/// A version is removed when any of the rules selects it. The default version, the version pinned by the project
+/// and linked binaries are never removed.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct PrunePolicy {
+    /// Keep only the given number of the newest nightlies
+    pub keep_nightlies: Option<usize>,
+    /// Remove nightlies of versions that have a stable release
+    pub superseded: bool,
+    /// Remove versions that weren't used for the given time
+    pub unused_for: Option<Duration>,
+    /// Remove versions matching the requirement
+    pub matching: Option<VersionReq>,
+}
+
+impl PrunePolicy {
+    /// Whether no rule is set, such policies don't remove anything
+    pub fn is_empty(&self) -> bool {
+        self == &Self::default()
+    }
+}
+
+/// Installed version considered for pruning.
+pub(crate) struct Installed {
+    pub(crate) version: Version,
+    /// Last time the version was used, or installed if it was never used
+    pub(crate) last_used: Option<SystemTime>,
+}
+
+/// Versions selected for removal by the policy, in ascending order
+///
+/// # Arguments
+///
+/// * `installed` - installed versions, without linked binaries
+/// * `stable` - known stable versions, installed or released
+/// * `protected` - versions that are never removed
+pub(crate) fn select(
+    policy: &PrunePolicy,
+    installed: &[Installed],
+    stable: &BTreeSet<Version>,
+    protected: &BTreeSet<Version>,
+    now: SystemTime,
+) -> Vec<Version> {
+    let mut nightlies: Vec<&Version> = installed
+        .iter()
+        .map(|installed| &installed.version)
+        .filter(|version| Channel::of(version) == Channel::Nightly)
+        .collect();
+    nightlies.sort();
+    let old_nightlies: BTreeSet<&Version> = match policy.keep_nightlies {
+        Some(keep) => nightlies[..nightlies.len().saturating_sub(keep)]
+            .iter()
+            .copied()
+            .collect(),
+        None => BTreeSet::new(),
+    };
+
+    let mut selected: Vec<Version> = installed
+        .iter()
+        .filter(|installed| {
+            let version = &installed.version;
+            let superseded = policy.superseded
+                && Channel::of(version) == Channel::Nightly
+                && stable.iter().any(|stable| stable > version);
+            let unused = policy.unused_for.is_some_and(|unused_for| {
+                installed.last_used.is_some_and(|last_used| {
+                    now.duration_since(last_used).unwrap_or_default() >= unused_for
+                })
+            });
+            let matching = policy
+                .matching
+                .as_ref()
+                .is_some_and(|req| req.matches(version));
+            old_nightlies.contains(version) || superseded || unused || matching
+        })
+        .map(|installed| installed.version.clone())
+        .filter(|version| !protected.contains(version))
+        .collect();
+    selected.sort();
+    selected
+}
+
+#[cfg(test)]
+mod test {
+    use std::{
+        collections::BTreeSet,
+        time::{Duration, SystemTime},
+    };
+
+    use semver::{Version, VersionReq};
+
+    use super::{select, Installed, PrunePolicy};
+
+    const DAY: Duration = Duration::from_secs(86_400);
+
+    fn installed(now: SystemTime) -> Vec<Installed> {
+        [
+            ("0.2.0", 100),
+            ("0.3.0-nightly.2025.7.8", 1),
+            ("0.3.0-nightly.2025.7.9", 40),
+            ("0.4.0-nightly.2025.8.9", 2),
+            ("0.4.0-nightly.2025.8.10", 3),
+            ("0.3.0", 0),
+        ]
+        .into_iter()
+        .map(|(version, days)| Installed {
+            version: Version::parse(version).unwrap(),
+            last_used: Some(now - DAY * days),
+        })
+        .collect()
+    }
+
+    fn versions(selected: Vec<Version>) -> Vec<String> {
+        selected.iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn policies() {
+        let now = SystemTime::now();
+        let installed = installed(now);
+        let stable = BTreeSet::from([Version::new(0, 2, 0), Version::new(0, 3, 0)]);
+        let none = BTreeSet::new();
+        let prune = |policy: PrunePolicy, protected: &BTreeSet<Version>| {
+            versions(select(&policy, &installed, &stable, protected, now))
+        };
+
+        assert!(prune(PrunePolicy::default(), &none).is_empty());
+        assert_eq!(
+            prune(
+                PrunePolicy {
+                    keep_nightlies: Some(2),
+                    ..Default::default()
+                },
+                &none
+            ),
+            ["0.3.0-nightly.2025.7.8", "0.3.0-nightly.2025.7.9"]
+        );
+        assert_eq!(
+            prune(
+                PrunePolicy {
+                    superseded: true,
+                    ..Default::default()
+                },
+                &none
+            ),
+            ["0.3.0-nightly.2025.7.8", "0.3.0-nightly.2025.7.9"]
+        );
+        assert_eq!(
+            prune(
+                PrunePolicy {
+                    unused_for: Some(DAY * 30),
+                    ..Default::default()
+                },
+                &none
+            ),
+            ["0.2.0", "0.3.0-nightly.2025.7.9"]
+        );
+        assert_eq!(
+            prune(
+                PrunePolicy {
+                    matching: Some(VersionReq::parse("<0.3.0-nightly.2025.7.9").unwrap()),
+                    unused_for: Some(DAY * 30),
+                    ..Default::default()
+                },
+                &BTreeSet::from([Version::new(0, 2, 0)])
+            ),
+            ["0.3.0-nightly.2025.7.8", "0.3.0-nightly.2025.7.9"]
+        );
+    }
+}
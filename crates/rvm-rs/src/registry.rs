@@ -0,0 +1,139 @@
+use std::{fmt::Display, str::FromStr};
+
+use semver::Version;
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::errors::Error;
+
+/// Name of the built-in registry that serves the official stable and nightly releases.
+pub const OFFICIAL_REGISTRY: &str = "official";
+
+/// Registry whose releases couldn't be fetched, its versions are left out of the release list.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct UnavailableRegistry {
+    /// Name of the registry
+    pub name: String,
+    /// Why the releases couldn't be fetched
+    pub reason: String,
+}
+
+/// Build of a registry that was left out of the release list because its artifact is invalid.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct SkippedBuild {
+    /// Name of the registry, `None` until the release list is assigned to a registry
+    pub registry: Option<String>,
+    /// Reference of the artifact, e.g. `compilers/resolc/linux:0.4.0`
+    pub reference: String,
+    /// Why the build was skipped
+    pub reason: String,
+}
+
+/// Named release index (`list.json`) that Resolc versions can be installed from.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Registry {
+    /// Registry name used to select it explicitly, e.g. `internal` in `internal:0.4.0`
+    pub name: String,
+    /// URL of the `list.json` release index, of a release repository root (ending with `/`) or of an OCI repository
+    pub url: Url,
+    /// Registries with higher priority win when several of them provide the same version.
+    /// The official registry has priority `0`.
+    #[serde(default)]
+    pub priority: i32,
+}
+
+impl Registry {
+    /// Create a new registry description
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - name of the registry, can't be empty, contain `:` or clash with [`OFFICIAL_REGISTRY`]
+    /// * `url` - URL of the `list.json` release index
+    /// * `priority` - priority of the registry relative to others
+    pub fn new(name: impl Into<String>, url: Url, priority: i32) -> Result<Self, Error> {
+        let name = name.into();
+        if name.is_empty() || name.contains(':') || name.contains(char::is_whitespace) {
+            return Err(Error::InvalidRegistryName { name });
+        }
+        if name == OFFICIAL_REGISTRY {
+            return Err(Error::ReservedRegistryName { name });
+        }
+        Ok(Self {
+            name,
+            url,
+            priority,
+        })
+    }
+}
+
+/// Resolc version optionally qualified with the registry it should be installed from.
+///
+/// Parsed from `<version>` or `<registry>:<version>`, e.g. `0.4.0` or `internal:0.4.0-patch.1`.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct RegistryVersion {
+    /// Registry to select the version from, any registry is used when not set.
+    pub registry: Option<String>,
+    /// Resolc version
+    pub version: Version,
+}
+
+impl FromStr for RegistryVersion {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.split_once(':') {
+            Some((registry, version)) => Ok(Self {
+                registry: Some(registry.to_owned()),
+                version: Version::parse(version)?,
+            }),
+            None => Ok(Self {
+                registry: None,
+                version: Version::parse(s)?,
+            }),
+        }
+    }
+}
+
+impl Display for RegistryVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.registry {
+            Some(registry) => write!(f, "{registry}:{}", self.version),
+            None => write!(f, "{}", self.version),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use semver::Version;
+
+    use super::{Registry, RegistryVersion};
+
+    #[test]
+    fn parse_registry_version() {
+        let plain: RegistryVersion = "0.4.0".parse().unwrap();
+        assert_eq!(plain.registry, None);
+        assert_eq!(plain.version, Version::new(0, 4, 0));
+
+        let qualified: RegistryVersion = "internal:0.4.0-patch.1".parse().unwrap();
+        assert_eq!(qualified.registry.as_deref(), Some("internal"));
+        assert_eq!(qualified.version, Version::parse("0.4.0-patch.1").unwrap());
+        assert_eq!(qualified.to_string(), "internal:0.4.0-patch.1");
+
+        assert!("internal:latest".parse::<RegistryVersion>().is_err());
+    }
+
+    #[test]
+    fn registry_names() {
+        let url = url::Url::parse("https://example.com/list.json").unwrap();
+        Registry::new("internal", url.clone(), 1).unwrap();
+        assert_eq!(
+            Registry::new("official", url.clone(), 1)
+                .unwrap_err()
+                .to_string(),
+            "Registry name \"official\" is reserved"
+        );
+        assert!(Registry::new("a:b", url.clone(), 1).is_err());
+        assert!(Registry::new("", url, 1).is_err());
+    }
+}
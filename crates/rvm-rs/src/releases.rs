@@ -1,5 +1,5 @@
 use std::{
-    collections::BTreeMap,
+    collections::{BTreeMap, BTreeSet},
     path::{Path, PathBuf},
     time::Duration,
 };
@@ -9,7 +9,13 @@ use serde::{Deserialize, Serialize};
 use sha2::Digest;
 use url::Url;
 
-use crate::{constants::MIN_VERSION, errors::Error};
+use crate::{
+    constants::{Platform, MIN_VERSION},
+    errors::Error,
+    local::Link,
+    oci::OciRepository,
+    registry::{SkippedBuild, UnavailableRegistry},
+};
 
 /// Resolc equivalent of `list.json` of `solc` releases.
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -18,23 +24,117 @@ pub struct Releases {
     pub(crate) releases: BTreeMap<Version, String>,
     #[serde(rename = "latestRelease")]
     pub(crate) latest_release: Version,
+    /// Registries that were skipped because their releases couldn't be fetched
+    #[serde(skip)]
+    pub(crate) unavailable: Vec<UnavailableRegistry>,
+    /// Builds of registries that were left out because their artifacts are invalid
+    #[serde(skip)]
+    pub(crate) skipped: Vec<SkippedBuild>,
 }
 
 impl Releases {
-    /// Grabs all releases from the remote `url`.
+    /// Grabs all releases from the remote `url`, `file://` URLs are read from the filesystem.
+    ///
+    /// OCI registries (`oci://`, `oci+http://` and `oci+https://` URLs) are listed through the distribution API.
+    /// URLs ending with `/` are release repositories with the same layout as [`crate::REPO_URL`],
+    /// stable and nightly releases of the current platform are merged.
     pub fn new(url: url::Url) -> Result<Releases, Error> {
-        reqwest::blocking::get(url)?.json().map_err(Into::into)
+        if OciRepository::is_oci(&url) {
+            return OciRepository::from_url(&url, &Platform::get()?)?.releases();
+        }
+        if url.path().ends_with('/') {
+            let platform = Platform::get()?;
+            let mut releases = Releases::new(platform.list_url(&url, false)?)?;
+            releases.merge(&mut Releases::new(platform.list_url(&url, true)?)?);
+            return Ok(releases);
+        }
+        let list = if url.scheme() == "file" {
+            std::fs::read_to_string(file_path(&url)?)?
+        } else {
+            reqwest::blocking::get(url.clone())?.text()?
+        };
+        Self::parse_list(&url, &list)
+    }
+
+    /// Parses a `list.json`, build URLs relative to the list are resolved against its URL
+    fn parse_list(url: &url::Url, list: &str) -> Result<Releases, Error> {
+        let mut list: serde_json::Value = serde_json::from_str(list)?;
+        for build in list
+            .get_mut("builds")
+            .and_then(serde_json::Value::as_array_mut)
+            .into_iter()
+            .flatten()
+        {
+            if let Some(build_url) = build.get_mut("url") {
+                if let Some(relative) = build_url.as_str() {
+                    *build_url = url.join(relative)?.to_string().into();
+                }
+            }
+        }
+        serde_json::from_value(list).map_err(Into::into)
+    }
+
+    /// Builds a release list out of individual builds.
+    ///
+    /// The highest version is used as the latest release, `0.0.0` if there are no builds.
+    pub(crate) fn from_builds(builds: Vec<Build>) -> Releases {
+        let releases = BTreeMap::from_iter(builds.iter().map(|data| {
+            (
+                data.version.clone(),
+                format!("{}+{}", data.name, data.long_version),
+            )
+        }));
+
+        let latest_release = releases
+            .keys()
+            .next_back()
+            .cloned()
+            .unwrap_or_else(|| Version::new(0, 0, 0));
+
+        Releases {
+            builds,
+            releases,
+            latest_release,
+            unavailable: Vec::new(),
+            skipped: Vec::new(),
+        }
     }
 
+    /// Marks every build as coming from the `registry`.
+    pub fn with_registry(mut self, registry: &str) -> Self {
+        for build in self.builds.iter_mut() {
+            build.registry = Some(registry.to_owned());
+        }
+        for skipped in self.skipped.iter_mut() {
+            skipped.registry = Some(registry.to_owned());
+        }
+        self
+    }
+
+    /// Merges builds of `other` into `self`.
+    ///
+    /// `self` takes precedence: builds of `other` are appended after the existing ones,
+    /// so when both provide the same version the build of `self` is picked by [`Releases::get_build`].
+    /// The same build coming from the same registry is only kept once.
     pub fn merge(&mut self, other: &mut Self) {
-        // merge builds with nightly
-        self.builds.extend_from_slice(&other.builds);
-        self.builds.dedup_by_key(|i| i.long_version.clone());
+        let known: BTreeSet<(Option<String>, Version)> = self
+            .builds
+            .iter()
+            .map(|build| (build.registry.clone(), build.version.clone()))
+            .collect();
+        self.builds.extend(
+            other
+                .builds
+                .drain(..)
+                .filter(|build| !known.contains(&(build.registry.clone(), build.version.clone()))),
+        );
 
-        // merge releases with nightly
-        self.releases.append(&mut other.releases);
+        for (version, name) in std::mem::take(&mut other.releases) {
+            self.releases.entry(version).or_insert(name);
+        }
+        self.skipped.append(&mut other.skipped);
 
-        // Note latest nightly is not set as latest release.
+        // Note latest release of `other` is never set as latest release.
     }
 
     /// Returns a build by Resolc version if it's present
@@ -46,6 +146,40 @@ impl Releases {
                 version: version.clone(),
             })
     }
+
+    /// Returns a build by Resolc version, limited to the given `registry` if it's set
+    pub fn get_build_in(&self, registry: Option<&str>, version: &Version) -> Result<&Build, Error> {
+        let Some(registry) = registry else {
+            return self.get_build(version);
+        };
+        self.builds
+            .iter()
+            .find(|item| item.version == *version && item.registry.as_deref() == Some(registry))
+            .ok_or_else(|| Error::UnknownRegistryVersion {
+                registry: registry.to_owned(),
+                version: version.clone(),
+            })
+    }
+}
+
+/// Hex encoded sha256 checksum of the binary
+pub(crate) fn sha256(bin: &[u8]) -> String {
+    let mut hasher: sha2::Sha256 = Digest::new();
+    hasher.update(bin);
+    hex::encode(hasher.finalize())
+}
+
+/// sha256 of the data read from the reader, for binaries that aren't loaded into memory
+pub(crate) fn sha256_reader(reader: &mut dyn std::io::Read) -> std::io::Result<String> {
+    let mut hasher: sha2::Sha256 = Digest::new();
+    std::io::copy(reader, &mut hasher)?;
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Converts a `file://` URL into a path
+pub(crate) fn file_path(url: &Url) -> Result<PathBuf, Error> {
+    url.to_file_path()
+        .map_err(|_| Error::InvalidFileUrl { url: url.clone() })
 }
 
 #[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
@@ -57,6 +191,12 @@ pub struct BinaryInfo {
     pub first_supported_solc_version: Version,
     /// last supported `solc` version
     pub last_supported_solc_version: Version,
+    /// Name of the registry the binary comes from, not set for binaries installed before registries were introduced
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub registry: Option<String>,
+    /// Name of the external binary registered with `rvm link`, such binaries aren't verified
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link: Option<String>,
 }
 
 #[derive(PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
@@ -141,6 +281,13 @@ impl Binary {
             Binary::Remote(info) => &info.version,
         }
     }
+    /// Returns basic info about the given `Binary`
+    pub fn info(&self) -> &BinaryInfo {
+        match self {
+            Binary::Local { info, .. } => info,
+            Binary::Remote(info) => info,
+        }
+    }
     /// Returns the path for the given `Binary`
     pub fn local(&self) -> Option<&Path> {
         match self {
@@ -163,10 +310,14 @@ pub struct Build {
     #[serde(rename = "lastSolcVersion")]
     pub(crate) last_supported_solc_version: Version,
     pub(crate) sha256: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) registry: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) link: Option<Link>,
 }
 
 impl Build {
-    fn verify_binary(&self, bin: &[u8]) -> Result<(), Error> {
+    pub(crate) fn verify_binary(&self, bin: &[u8]) -> Result<(), Error> {
         let checksum = hex::decode(&self.sha256)?;
         let checksum_from_binary = {
             let mut hasher: sha2::Sha256 = Digest::new();
@@ -219,6 +370,11 @@ impl Build {
 
     /// Downloads the binary for the given version
     pub fn download_binary(&self) -> Result<Vec<u8>, Error> {
+        if self.url.scheme() == "file" {
+            let binary = std::fs::read(file_path(&self.url)?)?;
+            self.verify_binary(&binary)?;
+            return Ok(binary);
+        }
         let binary = reqwest::blocking::ClientBuilder::new()
             .timeout(Duration::from_secs(300))
             .build()?
@@ -231,13 +387,38 @@ impl Build {
         Ok(binary.to_vec())
     }
 
+    /// Whether the build is an external binary registered with `rvm link`
+    pub(crate) fn is_link(&self) -> bool {
+        self.link.is_some()
+    }
+
     pub(crate) fn into_local(self, path: &Path) -> Binary {
+        let (path, link) = match self.link {
+            Some(link) => (link.path, Some(link.name)),
+            None => (path.join(self.version.to_string()).join(self.name), None),
+        };
+        Binary::Local {
+            path,
+            info: BinaryInfo {
+                version: self.version,
+                first_supported_solc_version: self.first_supported_solc_version,
+                last_supported_solc_version: self.last_supported_solc_version,
+                registry: self.registry,
+                link,
+            },
+        }
+    }
+
+    /// Binary outside of the store, e.g. selected with `RVM_RESOLC_PATH`
+    pub(crate) fn into_external(self, path: PathBuf) -> Binary {
         Binary::Local {
-            path: path.join(self.version.to_string()).join(self.name),
+            path,
             info: BinaryInfo {
                 version: self.version,
                 first_supported_solc_version: self.first_supported_solc_version,
                 last_supported_solc_version: self.last_supported_solc_version,
+                registry: self.registry,
+                link: None,
             },
         }
     }
@@ -247,6 +428,8 @@ impl Build {
             version: self.version,
             first_supported_solc_version: self.first_supported_solc_version,
             last_supported_solc_version: self.last_supported_solc_version,
+            registry: self.registry,
+            link: None,
         })
     }
 }
@@ -288,6 +471,53 @@ mod test {
             .unwrap()
     }
 
+    #[test]
+    fn releases_from_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("list.json");
+        std::fs::write(&path, release()).unwrap();
+
+        let releases = Releases::new(url::Url::from_file_path(&path).unwrap()).unwrap();
+        assert_eq!(releases, serde_json::from_str(release()).unwrap());
+    }
+
+    #[test]
+    fn merge_registries() {
+        let mut official = serde_json::from_str::<Releases>(release())
+            .unwrap()
+            .with_registry("official");
+        let mut internal = serde_json::from_str::<Releases>(release())
+            .unwrap()
+            .with_registry("internal");
+        let mut duplicate = serde_json::from_str::<Releases>(release())
+            .unwrap()
+            .with_registry("official");
+        official.merge(&mut duplicate);
+        official.merge(&mut internal);
+
+        let version = Version::parse("0.1.0-dev.13").unwrap();
+        assert_eq!(official.builds.len(), 2);
+        assert_eq!(
+            official.get_build(&version).unwrap().registry.as_deref(),
+            Some("official")
+        );
+        assert_eq!(
+            official
+                .get_build_in(Some("internal"), &version)
+                .unwrap()
+                .registry
+                .as_deref(),
+            Some("internal")
+        );
+        assert_eq!(
+            official
+                .get_build_in(Some("other"), &version)
+                .unwrap_err()
+                .to_string(),
+            "Unknown version of Resolc v0.1.0-dev.13 in registry \"other\"."
+        );
+    }
+
     #[test]
     fn solc_version_support() {
         let build = r#"
@@ -0,0 +1,268 @@
+use std::{
+    fmt::Display,
+    path::{Path, PathBuf},
+};
+
+use serde::Serialize;
+
+use semver::Version;
+
+use crate::{
+    errors::Error,
+    fs::{DataDir, FsPaths},
+    lockfile::{Lockfile, LOCK_FILE_NAME},
+    releases::Binary,
+    tools::{self, PROJECT_CONFIGS_ENV},
+};
+
+/// Environment variable that selects the Resolc version, an exact version, a specification or a link name.
+pub const VERSION_ENV: &str = "RESOLC_VERSION";
+
+/// Environment variable with the path of the Resolc binary to use, it takes precedence over [`VERSION_ENV`].
+pub const PATH_ENV: &str = "RVM_RESOLC_PATH";
+
+/// Name of the file that pins the Resolc version of a project, looked up in the current directory and its parents.
+pub const PROJECT_FILE_NAME: &str = ".resolc-version";
+
+/// Rule that requests a Resolc version.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Source {
+    /// `resolc +<selector>` argument
+    Argument,
+    /// Environment variable
+    Env {
+        /// Name of the variable
+        variable: String,
+    },
+    /// `.resolc-version` file of the project
+    ProjectFile {
+        /// Path to the file
+        path: PathBuf,
+    },
+    /// `rvm.lock` of the project, it wins over the project file in the same folder
+    LockFile {
+        /// Path to the file
+        path: PathBuf,
+    },
+    /// Resolc settings of `foundry.toml` or a hardhat configuration, read only when enabled
+    ToolConfig {
+        /// Path to the configuration file
+        path: PathBuf,
+        /// Foundry profile, `None` for hardhat
+        profile: Option<String>,
+        /// `solc` version requested next to Resolc
+        solc: Option<String>,
+    },
+    /// Global default set with `rvm use`
+    Default {
+        /// Path to the file the default is stored in
+        path: PathBuf,
+    },
+}
+
+impl Display for Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Source::Argument => f.write_str("`+<version>` argument"),
+            Source::Env { variable } => write!(f, "environment variable {variable}"),
+            Source::ProjectFile { path } => write!(f, "project file {}", path.display()),
+            Source::LockFile { path } => write!(f, "lockfile {}", path.display()),
+            Source::ToolConfig {
+                path,
+                profile: Some(profile),
+                ..
+            } => write!(f, "profile {profile} of {}", path.display()),
+            Source::ToolConfig { path, .. } => {
+                write!(f, "project configuration {}", path.display())
+            }
+            Source::Default { path } => write!(f, "global default {}", path.display()),
+        }
+    }
+}
+
+/// Resolc version requested by one of the rules.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Candidate {
+    /// Rule that requests the version
+    pub source: Source,
+    /// Requested version, version specification, name of a linked binary or path to a binary
+    pub selector: String,
+}
+
+impl Candidate {
+    /// Whether the selector is a path to a binary rather than a version
+    pub fn is_path(&self) -> bool {
+        match &self.source {
+            Source::Env { variable } => variable == PATH_ENV,
+            Source::ToolConfig { .. } => tools::is_path(&self.selector),
+            _ => false,
+        }
+    }
+}
+
+/// Resolc binary that runs as `resolc` and the rule that selected it.
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct Resolution {
+    /// Candidate that won
+    pub selected: Candidate,
+    /// Candidates with lower precedence, ignored because of the selected one
+    pub shadowed: Vec<Candidate>,
+    /// Selected binary
+    pub binary: Binary,
+}
+
+/// Collects the requested versions by precedence: the argument, the environment variables,
+/// the closest project file, foundry or hardhat configuration when enabled and the global default.
+pub(crate) fn candidates(
+    fs: &dyn FsPaths,
+    argument: Option<&str>,
+    env: &dyn Fn(&str) -> Option<String>,
+    dir: &Path,
+    default: Option<Version>,
+) -> Result<Vec<Candidate>, Error> {
+    let mut candidates = Vec::new();
+    if let Some(selector) = argument {
+        candidates.push(Candidate {
+            source: Source::Argument,
+            selector: selector.to_owned(),
+        });
+    }
+    for variable in [PATH_ENV, VERSION_ENV] {
+        if let Some(selector) = env(variable).filter(|selector| !selector.trim().is_empty()) {
+            candidates.push(Candidate {
+                source: Source::Env {
+                    variable: variable.to_owned(),
+                },
+                selector: selector.trim().to_owned(),
+            });
+        }
+    }
+    candidates.extend(project_file(dir)?);
+    let project_configs = match env(PROJECT_CONFIGS_ENV) {
+        Some(value) if !value.is_empty() => matches!(value.as_str(), "1" | "true"),
+        _ => fs.read_config().is_ok_and(|config| config.project_configs),
+    };
+    if project_configs {
+        if let Some(version) = tools::requested(dir, env)? {
+            if let Some(selector) = version.resolc {
+                candidates.push(Candidate {
+                    source: Source::ToolConfig {
+                        path: version.path,
+                        profile: version.profile,
+                        solc: version.solc,
+                    },
+                    selector,
+                });
+            }
+        }
+    }
+    if let Some(version) = default {
+        candidates.push(Candidate {
+            source: Source::Default {
+                path: fs.default_version_path(),
+            },
+            selector: version.to_string(),
+        });
+    }
+    Ok(candidates)
+}
+
+/// Finds the binary `resolc` runs using only the store index, `None` when the full resolver is needed,
+/// e.g. for version specifications, missing versions or a missing index.
+pub(crate) fn indexed(argument: Option<&str>, dir: &Path) -> Option<PathBuf> {
+    let fs = DataDir::new().ok()?;
+    let index = fs.read_index()?;
+    let selected = candidates(
+        &fs,
+        argument,
+        &|variable| std::env::var(variable).ok(),
+        dir,
+        index.default.clone(),
+    )
+    .ok()?
+    .into_iter()
+    .next()?;
+    let path = if selected.is_path() {
+        PathBuf::from(&selected.selector)
+    } else {
+        index.binary(&selected.selector)?.to_path_buf()
+    };
+    path.is_file().then_some(path)
+}
+
+/// Finds the closest `rvm.lock` or `.resolc-version` file with a version in it, a lockfile wins in the same folder
+pub(crate) fn project_file(dir: &Path) -> Result<Option<Candidate>, Error> {
+    for dir in dir.ancestors() {
+        let lock = dir.join(LOCK_FILE_NAME);
+        if lock.is_file() {
+            return Ok(Some(Candidate {
+                selector: Lockfile::read(&lock)?.version.to_string(),
+                source: Source::LockFile { path: lock },
+            }));
+        }
+        let path = dir.join(PROJECT_FILE_NAME);
+        let content = match std::fs::read_to_string(&path) {
+            Ok(content) => content,
+            Err(err)
+                if matches!(
+                    err.kind(),
+                    std::io::ErrorKind::NotFound | std::io::ErrorKind::NotADirectory
+                ) =>
+            {
+                continue
+            }
+            Err(err) => return Err(err.into()),
+        };
+        if let Some(selector) = content.lines().map(str::trim).find(|line| !line.is_empty()) {
+            return Ok(Some(Candidate {
+                source: Source::ProjectFile { path },
+                selector: selector.to_owned(),
+            }));
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod test {
+    use semver::Version;
+
+    use super::{candidates, Source, PATH_ENV, PROJECT_FILE_NAME, VERSION_ENV};
+    use crate::{fs::FsPaths, test::TempDir};
+
+    #[test]
+    fn precedence() {
+        let store = TempDir::new().unwrap();
+        let project = tempfile::tempdir().unwrap();
+        let nested = project.path().join("contracts").join("src");
+        std::fs::create_dir_all(&nested).unwrap();
+
+        let no_env = |_: &str| None;
+        assert!(candidates(&store, None, &no_env, &nested, None)
+            .unwrap()
+            .is_empty());
+
+        let default = Some(Version::new(0, 3, 0));
+        std::fs::write(project.path().join(PROJECT_FILE_NAME), "\n0.4.0\n").unwrap();
+        let env = |variable: &str| match variable {
+            PATH_ENV => Some("/opt/resolc".to_owned()),
+            VERSION_ENV => Some("0.2.0".to_owned()),
+            _ => None,
+        };
+        let found = candidates(&store, Some("dev"), &env, &nested, default.clone()).unwrap();
+        let selectors: Vec<_> = found.iter().map(|c| c.selector.as_str()).collect();
+        assert_eq!(selectors, ["dev", "/opt/resolc", "0.2.0", "0.4.0", "0.3.0"]);
+        assert_eq!(found[0].source, Source::Argument);
+        assert_eq!(
+            found[3].source,
+            Source::ProjectFile {
+                path: project.path().join(PROJECT_FILE_NAME)
+            }
+        );
+
+        let blank = |_: &str| Some(" ".to_owned());
+        let found = candidates(&store, None, &blank, &nested, default).unwrap();
+        assert_eq!(found[0].selector, "0.4.0");
+    }
+}
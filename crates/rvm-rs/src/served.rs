@@ -0,0 +1,155 @@
+use std::path::{Path, PathBuf};
+
+use semver::Version;
+use url::Url;
+
+use crate::{
+    constants::Platform,
+    errors::Error,
+    fs::{DataDir, FsPaths},
+    mirror::Mirror,
+    releases::{Build, Releases},
+    spec::Channel,
+};
+
+/// Builds available on the local filesystem that can be served as a release repository.
+///
+/// Release lists use the same layout as [`crate::REPO_URL`]: `<platform>/list.json` for stable and
+/// `nightly/<platform>/list.json` for nightly releases, binaries are served as `<platform>/<version>/<binary>`.
+pub struct ServedReleases {
+    platform: Platform,
+    builds: Vec<(Build, PathBuf)>,
+}
+
+impl ServedReleases {
+    pub(crate) fn new(platform: Platform, builds: Vec<(Build, PathBuf)>) -> Self {
+        Self { platform, builds }
+    }
+
+    /// Builds installed in the default installation folder, linked binaries aren't served
+    pub fn from_store() -> Result<Self, Error> {
+        let store = DataDir::new()?;
+        let builds = store
+            .installed_versions()?
+            .into_iter()
+            .filter(|build| !build.is_link())
+            .map(|build| {
+                let path = store
+                    .path()
+                    .join(build.version.to_string())
+                    .join(&build.name);
+                (build, path)
+            })
+            .collect();
+        Ok(Self::new(Platform::get()?, builds))
+    }
+
+    /// Builds of the platform present in the mirror
+    pub fn from_mirror(mirror: &Mirror, platform: Platform) -> Result<Self, Error> {
+        let mut builds = Vec::new();
+        for channel in [Channel::Stable, Channel::Nightly] {
+            match Releases::new(mirror.list_url(&platform, channel)?) {
+                Ok(releases) => builds.extend(releases.builds),
+                Err(Error::IoError(err)) if err.kind() == std::io::ErrorKind::NotFound => {}
+                Err(err) => return Err(err),
+            }
+        }
+        let builds = builds
+            .into_iter()
+            .filter_map(|build| {
+                let path = build.url.to_file_path().ok()?;
+                Some((build, path))
+            })
+            .collect();
+        Ok(Self::new(platform, builds))
+    }
+
+    /// Platform of the builds
+    pub fn platform(&self) -> Platform {
+        self.platform
+    }
+
+    /// Release list of the channel in the `list.json` format, binary URLs point to `base`
+    pub fn list(&self, channel: Channel, base: &Url) -> Result<String, Error> {
+        let builds = self
+            .builds
+            .iter()
+            .filter(|(build, _)| Channel::of(&build.version) == channel)
+            .map(|(build, _)| {
+                let mut build = build.clone();
+                build.url = base.join(&self.binary_path(&build.version, &build.name))?;
+                build.registry = None;
+                Ok(build)
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        serde_json::to_string_pretty(&Releases::from_builds(builds)).map_err(Into::into)
+    }
+
+    /// Local path of the binary that is served as `<platform>/<version>/<name>`
+    pub fn binary(&self, version: &Version, name: &str) -> Option<&Path> {
+        self.builds
+            .iter()
+            .find(|(build, _)| build.version == *version && build.name == name)
+            .map(|(_, path)| path.as_path())
+    }
+
+    fn binary_path(&self, version: &Version, name: &str) -> String {
+        format!("{}/{version}/{name}", self.platform.name())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+
+    use semver::Version;
+    use url::Url;
+
+    use super::ServedReleases;
+    use crate::{constants::Platform, releases::Build, spec::Channel};
+
+    fn build(version: &str) -> (Build, PathBuf) {
+        let build = serde_json::from_value(serde_json::json!({
+            "name": "resolc-x86_64-unknown-linux-musl",
+            "version": version,
+            "longVersion": format!("{version}+commit.0000000"),
+            "url": "https://example.com/resolc",
+            "sha256": "00",
+            "firstSolcVersion": "0.8.0",
+            "lastSolcVersion": "0.8.30",
+            "registry": "official"
+        }))
+        .unwrap();
+        (build, PathBuf::from(format!("/store/{version}/resolc")))
+    }
+
+    #[test]
+    fn lists_per_channel() {
+        let served = ServedReleases::new(
+            Platform::Linux,
+            vec![build("0.3.0-nightly.2025.9.30"), build("0.4.0")],
+        );
+        let base = Url::parse("http://cache.local:8080/").unwrap();
+
+        let stable: serde_json::Value =
+            serde_json::from_str(&served.list(Channel::Stable, &base).unwrap()).unwrap();
+        assert_eq!(stable["latestRelease"], "0.4.0");
+        assert_eq!(stable["builds"].as_array().unwrap().len(), 1);
+        assert_eq!(
+            stable["builds"][0]["url"],
+            "http://cache.local:8080/linux/0.4.0/resolc-x86_64-unknown-linux-musl"
+        );
+        assert!(stable["builds"][0].get("registry").is_none());
+
+        let nightly: serde_json::Value =
+            serde_json::from_str(&served.list(Channel::Nightly, &base).unwrap()).unwrap();
+        assert_eq!(nightly["latestRelease"], "0.3.0-nightly.2025.9.30");
+
+        assert_eq!(
+            served.binary(&Version::new(0, 4, 0), "resolc-x86_64-unknown-linux-musl"),
+            Some(PathBuf::from("/store/0.4.0/resolc").as_path())
+        );
+        assert_eq!(served.binary(&Version::new(0, 4, 0), "resolc"), None);
+    }
+}
@@ -0,0 +1,137 @@
+use std::{
+    collections::BTreeSet,
+    env::consts::EXE_SUFFIX,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{errors::Error, index::StoreIndex};
+
+/// Folder of the store with the shims, it's meant to be added to `PATH`.
+pub(crate) const SHIM_DIR: &str = "bin";
+
+/// Prefix of the versioned shims, `resolc-<version>` runs the given version and `resolc-<name>` the linked binary
+/// or the alias.
+pub(crate) const SHIM_PREFIX: &str = "resolc-";
+
+/// Makes `<store>/bin` contain the `resolc` wrapper and a `resolc-<version>` shim for every installed version, link
+/// and alias selecting an installed version, stale shims are removed.
+///
+/// The wrapper is taken from the folder of the running executable, `rvm` and `resolc` are installed together.
+/// Versioned shims point at the wrapper, which selects the version by the name it's started with.
+pub(crate) fn update(store: &Path, index: &StoreIndex) -> Result<(), Error> {
+    let dir = store.join(SHIM_DIR);
+    fs::create_dir_all(&dir)?;
+
+    let wrapper = dir.join(format!("resolc{EXE_SUFFIX}"));
+    // Dangling wrappers, e.g. after reinstalling `rvm` elsewhere, are replaced too.
+    if !wrapper.exists() {
+        if let Some(found) = find_wrapper(&wrapper) {
+            let _ = fs::remove_file(&wrapper);
+            install(&found, &wrapper)?;
+        }
+    }
+
+    let expected: BTreeSet<String> = index
+        .versions
+        .keys()
+        .map(ToString::to_string)
+        .chain(index.links.keys().cloned())
+        .chain(index.aliases.keys().cloned())
+        .map(|selector| format!("{SHIM_PREFIX}{selector}{EXE_SUFFIX}"))
+        .collect();
+    for entry in fs::read_dir(&dir)?.filter_map(|entry| entry.ok()) {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if name.starts_with(SHIM_PREFIX) && !expected.contains(&name) {
+            fs::remove_file(entry.path())?;
+        }
+    }
+    for name in expected {
+        let shim = dir.join(name);
+        if fs::symlink_metadata(&shim).is_err() {
+            #[cfg(unix)]
+            std::os::unix::fs::symlink(format!("resolc{EXE_SUFFIX}"), &shim)?;
+            #[cfg(not(unix))]
+            if wrapper.exists() {
+                fs::copy(&wrapper, &shim)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Selector of the versioned shim the program is started as, e.g. `0.4.0` for `resolc-0.4.0`
+pub fn shim_selector(program: &Path) -> Option<String> {
+    let name = program.file_name()?.to_str()?;
+    let name = name.strip_suffix(EXE_SUFFIX).unwrap_or(name);
+    name.strip_prefix(SHIM_PREFIX)
+        .filter(|selector| !selector.is_empty())
+        .map(ToOwned::to_owned)
+}
+
+/// `resolc` wrapper installed next to the running executable
+fn find_wrapper(shim: &Path) -> Option<PathBuf> {
+    let exe = std::env::current_exe().ok()?;
+    let wrapper = fs::canonicalize(exe.with_file_name(format!("resolc{EXE_SUFFIX}"))).ok()?;
+    (wrapper.is_file() && fs::canonicalize(shim).ok().as_ref() != Some(&wrapper)).then_some(wrapper)
+}
+
+fn install(wrapper: &Path, shim: &Path) -> Result<(), Error> {
+    #[cfg(unix)]
+    std::os::unix::fs::symlink(wrapper, shim)?;
+    #[cfg(not(unix))]
+    fs::copy(wrapper, shim)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use super::shim_selector;
+
+    #[test]
+    fn selector_from_program_name() {
+        assert_eq!(
+            shim_selector(Path::new("/home/user/.rvm/bin/resolc-0.4.0")).as_deref(),
+            Some("0.4.0")
+        );
+        assert_eq!(
+            shim_selector(Path::new("resolc-0.3.0-nightly.2025.8.9")).as_deref(),
+            Some("0.3.0-nightly.2025.8.9")
+        );
+        assert_eq!(
+            shim_selector(Path::new("resolc-dev")).as_deref(),
+            Some("dev")
+        );
+        assert_eq!(shim_selector(Path::new("/usr/bin/resolc")), None);
+        assert_eq!(shim_selector(Path::new("resolc-")), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn regenerated_with_the_store() {
+        use crate::{fs::FsPaths, test::TempDir};
+
+        let store = TempDir::new().unwrap();
+        let build: crate::Build = serde_json::from_value(serde_json::json!({
+            "name": "resolc",
+            "version": "0.4.0",
+            "longVersion": "0.4.0",
+            "url": "https://example.com/resolc",
+            "sha256": crate::releases::sha256(b"binary"),
+            "firstSolcVersion": "0.8.0",
+            "lastSolcVersion": "0.8.30"
+        }))
+        .unwrap();
+        store.install_version(&build, b"binary").unwrap();
+
+        let shim = store.path().join("bin").join("resolc-0.4.0");
+        assert_eq!(
+            std::fs::read_link(&shim).unwrap(),
+            Path::new("resolc").to_path_buf()
+        );
+        store.remove_version(&build.version).unwrap();
+        assert!(std::fs::symlink_metadata(&shim).is_err());
+    }
+}
@@ -0,0 +1,158 @@
+use std::{fmt::Display, str::FromStr};
+
+use semver::{Version, VersionReq};
+
+use crate::errors::Error;
+
+/// Release channel of a Resolc version.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Channel {
+    /// Stable and development releases
+    Stable,
+    /// Nightly builds, versions with a `nightly` pre-release
+    Nightly,
+}
+
+impl Channel {
+    /// Returns the channel the version is published in
+    pub fn of(version: &Version) -> Self {
+        if version.pre.as_str().starts_with("nightly") {
+            Channel::Nightly
+        } else {
+            Channel::Stable
+        }
+    }
+}
+
+impl FromStr for Channel {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "stable" => Ok(Channel::Stable),
+            "nightly" => Ok(Channel::Nightly),
+            _ => Err(Error::InvalidVersionSpec { spec: s.to_owned() }),
+        }
+    }
+}
+
+impl Display for Channel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Channel::Stable => f.write_str("stable"),
+            Channel::Nightly => f.write_str("nightly"),
+        }
+    }
+}
+
+/// Specification of a Resolc version.
+///
+/// Parsed from:
+/// * an exact version, e.g. `0.4.0`
+/// * a version requirement, e.g. `>=0.3.0, <0.5.0`
+/// * `latest` or `latest-nightly` for the newest version of the channel
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum VersionSpec {
+    /// Exact version
+    Exact(Version),
+    /// Newest version matching the requirement
+    Req(VersionReq),
+    /// Newest version of the channel
+    Latest(Channel),
+}
+
+impl VersionSpec {
+    /// Checks whether the version satisfies the spec, `Latest` is satisfied by any version of its channel
+    pub fn matches(&self, version: &Version) -> bool {
+        match self {
+            VersionSpec::Exact(exact) => exact == version,
+            VersionSpec::Req(req) => req.matches(version),
+            VersionSpec::Latest(channel) => Channel::of(version) == *channel,
+        }
+    }
+
+    /// Picks the newest version satisfying the spec
+    pub fn select<'a>(
+        &self,
+        versions: impl IntoIterator<Item = &'a Version>,
+    ) -> Option<&'a Version> {
+        versions
+            .into_iter()
+            .filter(|version| self.matches(version))
+            .max()
+    }
+}
+
+impl FromStr for VersionSpec {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        match s {
+            "latest" | "stable" => return Ok(VersionSpec::Latest(Channel::Stable)),
+            "latest-nightly" | "nightly" => return Ok(VersionSpec::Latest(Channel::Nightly)),
+            _ => {}
+        }
+        if let Ok(version) = Version::parse(s.trim_start_matches('v')) {
+            return Ok(VersionSpec::Exact(version));
+        }
+        VersionReq::parse(s)
+            .map(VersionSpec::Req)
+            .map_err(|_| Error::InvalidVersionSpec { spec: s.to_owned() })
+    }
+}
+
+impl Display for VersionSpec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            VersionSpec::Exact(version) => write!(f, "{version}"),
+            VersionSpec::Req(req) => write!(f, "{req}"),
+            VersionSpec::Latest(Channel::Stable) => f.write_str("latest"),
+            VersionSpec::Latest(Channel::Nightly) => f.write_str("latest-nightly"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use semver::Version;
+
+    use super::{Channel, VersionSpec};
+
+    #[test]
+    fn parse_and_select() {
+        let versions: Vec<Version> = ["0.2.0", "0.3.0-nightly.2025.9.30", "0.3.0", "0.4.0"]
+            .iter()
+            .map(|version| Version::parse(version).unwrap())
+            .collect();
+        let select = |spec: &str| {
+            spec.parse::<VersionSpec>()
+                .unwrap()
+                .select(&versions)
+                .map(ToString::to_string)
+        };
+
+        assert_eq!(select("0.3.0").as_deref(), Some("0.3.0"));
+        assert_eq!(select("v0.3.0").as_deref(), Some("0.3.0"));
+        assert_eq!(select("<0.4.0").as_deref(), Some("0.3.0"));
+        assert_eq!(select("latest").as_deref(), Some("0.4.0"));
+        assert_eq!(
+            select("latest-nightly").as_deref(),
+            Some("0.3.0-nightly.2025.9.30")
+        );
+        assert_eq!(select("0.5.0"), None);
+        assert!("newest".parse::<VersionSpec>().is_err());
+    }
+
+    #[test]
+    fn channel() {
+        assert_eq!(
+            Channel::of(&Version::parse("0.3.0-nightly.2025.7.8").unwrap()),
+            Channel::Nightly
+        );
+        assert_eq!(
+            Channel::of(&Version::parse("0.1.0-dev.13").unwrap()),
+            Channel::Stable
+        );
+    }
+}
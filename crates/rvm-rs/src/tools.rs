@@ -0,0 +1,377 @@
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+use crate::errors::Error;
+
+/// Environment variable that enables reading the Resolc version from foundry and hardhat configuration, `1` or `true`.
+pub const PROJECT_CONFIGS_ENV: &str = "RVM_PROJECT_CONFIGS";
+
+/// Environment variable foundry selects the profile with.
+const FOUNDRY_PROFILE_ENV: &str = "FOUNDRY_PROFILE";
+
+const FOUNDRY_CONFIG: &str = "foundry.toml";
+const DEFAULT_PROFILE: &str = "default";
+const HARDHAT_CONFIGS: [&str; 4] = [
+    "hardhat.config.ts",
+    "hardhat.config.js",
+    "hardhat.config.cjs",
+    "hardhat.config.mjs",
+];
+
+/// Compiler versions requested by a foundry profile or a hardhat configuration.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ToolVersion {
+    /// Path to the configuration file
+    pub path: PathBuf,
+    /// Foundry profile, `None` for hardhat
+    pub profile: Option<String>,
+    /// Requested Resolc version, version specification or absolute path to a binary
+    pub resolc: Option<String>,
+    /// Requested `solc` version
+    pub solc: Option<String>,
+}
+
+/// Reads the compiler versions of every profile of `foundry.toml`, or of a hardhat configuration
+///
+/// Foundry profiles request Resolc in `[profile.<name>.resolc]` with the `resolc` or `version` key and `solc` with
+/// the `solc` or `solc_version` key of the profile. Hardhat configuration is JavaScript, so it's only scanned for
+/// `resolc: { version: "..." }` and `solidity: "..."` (or `solidity: { version: "..." }`).
+pub fn read(path: &Path) -> Result<Vec<ToolVersion>, Error> {
+    let content = std::fs::read_to_string(path)?;
+    let dir = path.parent().unwrap_or(Path::new("."));
+    let is_hardhat = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name.starts_with("hardhat.config."));
+    let mut versions = if is_hardhat {
+        vec![hardhat(path, &content)]
+    } else {
+        foundry(path, &content)?
+    };
+    for version in &mut versions {
+        // Paths are relative to the configuration file.
+        if let Some(resolc) = &mut version.resolc {
+            if is_path(resolc) {
+                *resolc = dir.join(&*resolc).display().to_string();
+            }
+        }
+    }
+    Ok(versions)
+}
+
+/// Closest foundry or hardhat configuration of the directory, `foundry.toml` wins in the same folder
+pub(crate) fn find(dir: &Path) -> Option<PathBuf> {
+    dir.ancestors().find_map(|dir| {
+        std::iter::once(FOUNDRY_CONFIG)
+            .chain(HARDHAT_CONFIGS)
+            .map(|name| dir.join(name))
+            .find(|path| path.is_file())
+    })
+}
+
+/// Versions requested by the closest configuration for the profile selected with `FOUNDRY_PROFILE`.
+///
+/// Profiles without Resolc settings inherit them from the default profile, like in foundry.
+pub(crate) fn requested(
+    dir: &Path,
+    env: &dyn Fn(&str) -> Option<String>,
+) -> Result<Option<ToolVersion>, Error> {
+    let Some(path) = find(dir) else {
+        return Ok(None);
+    };
+    let versions = read(&path)?;
+    let profile = env(FOUNDRY_PROFILE_ENV).unwrap_or_else(|| DEFAULT_PROFILE.to_owned());
+    let find = |profile: &str| {
+        versions.iter().find(|version| {
+            version
+                .profile
+                .as_deref()
+                .is_none_or(|name| name == profile)
+        })
+    };
+    let (Some(selected), default) = (find(&profile), find(DEFAULT_PROFILE)) else {
+        return Ok(find(DEFAULT_PROFILE).cloned());
+    };
+    Ok(Some(ToolVersion {
+        resolc: selected
+            .resolc
+            .clone()
+            .or_else(|| default.and_then(|default| default.resolc.clone())),
+        solc: selected
+            .solc
+            .clone()
+            .or_else(|| default.and_then(|default| default.solc.clone())),
+        ..selected.clone()
+    }))
+}
+
+/// Whether the selector is a path to a binary rather than a version
+pub(crate) fn is_path(selector: &str) -> bool {
+    selector.contains(['/', '\\'])
+}
+
+fn foundry(path: &Path, content: &str) -> Result<Vec<ToolVersion>, Error> {
+    let config: toml::Table =
+        toml::from_str(content).map_err(|err| Error::InvalidProjectConfig {
+            path: path.to_path_buf(),
+            reason: err.message().to_owned(),
+        })?;
+    let Some(profiles) = config.get("profile").and_then(toml::Value::as_table) else {
+        return Ok(Vec::new());
+    };
+    let string = |table: &toml::Table, keys: &[&str]| {
+        keys.iter()
+            .find_map(|key| table.get(*key)?.as_str())
+            .map(ToOwned::to_owned)
+    };
+    Ok(profiles
+        .iter()
+        .filter_map(|(name, profile)| {
+            let profile_table = profile.as_table()?;
+            let resolc = profile_table
+                .get("resolc")
+                .and_then(toml::Value::as_table)
+                .and_then(|resolc| string(resolc, &["resolc", "version"]));
+            Some(ToolVersion {
+                path: path.to_path_buf(),
+                profile: Some(name.clone()),
+                resolc,
+                solc: string(profile_table, &["solc", "solc_version"]),
+            })
+        })
+        .collect())
+}
+
+fn hardhat(path: &Path, content: &str) -> ToolVersion {
+    let resolc = find_key(content, "resolc", true)
+        .and_then(|start| object(&content[start..]))
+        .and_then(|resolc| {
+            find_key(resolc, "version", false).and_then(|start| string(&resolc[start..]))
+        });
+    let solc = find_key(content, "solidity", true).and_then(|start| {
+        let solidity = &content[start..];
+        string(solidity).or_else(|| {
+            let solidity = object(solidity)?;
+            find_key(solidity, "version", false).and_then(|start| string(&solidity[start..]))
+        })
+    });
+    ToolVersion {
+        path: path.to_path_buf(),
+        profile: None,
+        resolc,
+        solc,
+    }
+}
+
+/// Contents of the object literal at the start of `source`, without the braces
+fn object(source: &str) -> Option<&str> {
+    if !source.starts_with('{') {
+        return None;
+    }
+    let mut depth = 0;
+    let mut end = None;
+    scan(source, |i, c, _| {
+        match c {
+            b'{' | b'[' | b'(' => depth += 1,
+            b'}' | b']' | b')' => {
+                depth -= 1;
+                if depth == 0 {
+                    end = Some(i);
+                    return true;
+                }
+            }
+            _ => {}
+        }
+        false
+    });
+    end.map(|end| &source[1..end])
+}
+
+/// String literal at the start of `source`
+fn string(source: &str) -> Option<String> {
+    let quote = source
+        .chars()
+        .next()
+        .filter(|c| matches!(c, '"' | '\'' | '`'))?;
+    let end = source[1..].find(quote)?;
+    Some(source[1..=end].to_owned())
+}
+
+/// Position of the value of `key: <value>` in JavaScript source, skipping strings and comments
+///
+/// # Arguments
+///
+/// * `nested` - also look into nested objects, otherwise only keys of the outermost level match
+fn find_key(source: &str, key: &str, nested: bool) -> Option<usize> {
+    let is_ident = |c: u8| c.is_ascii_alphanumeric() || matches!(c, b'_' | b'$');
+    let bytes = source.as_bytes();
+    let mut depth = 0;
+    let mut found = None;
+    // Offsets may fall inside multi-byte characters, so they are compared as bytes. The key is ASCII, so a match
+    // starts and ends on character boundaries.
+    scan(source, |i, c, quoted| {
+        match c {
+            b'{' | b'[' | b'(' => depth += 1,
+            b'}' | b']' | b')' => depth -= 1,
+            _ if (nested || depth == 0)
+                && bytes[i..].starts_with(key.as_bytes())
+                && (quoted || i == 0 || !is_ident(bytes[i - 1]))
+                && !bytes
+                    .get(i + key.len())
+                    .is_some_and(|c| is_ident(*c) && !quoted) =>
+            {
+                let rest = source[i + key.len()..].trim_start_matches(['"', '\'']);
+                if let Some(value) = rest.trim_start().strip_prefix(':') {
+                    found = Some(source.len() - value.trim_start().len());
+                    return true;
+                }
+            }
+            _ => {}
+        }
+        false
+    });
+    found
+}
+
+/// Calls `f` with the position of every byte outside of comments and string literals, and of the first byte
+/// of every string literal's contents (marked as quoted), until `f` returns `true`
+fn scan(source: &str, mut f: impl FnMut(usize, u8, bool) -> bool) {
+    let bytes = source.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            quote @ (b'"' | b'\'' | b'`') => {
+                if i + 1 < bytes.len() && f(i + 1, bytes[i + 1], true) {
+                    return;
+                }
+                i += 1;
+                while i < bytes.len() && bytes[i] != quote {
+                    i += if bytes[i] == b'\\' { 2 } else { 1 };
+                }
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    i += 1;
+                }
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                i += 2;
+                while i + 1 < bytes.len() && !(bytes[i] == b'*' && bytes[i + 1] == b'/') {
+                    i += 1;
+                }
+                i += 1;
+            }
+            c => {
+                if f(i, c, false) {
+                    return;
+                }
+            }
+        }
+        i += 1;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::Path;
+
+    use super::{find, read, requested, ToolVersion};
+
+    #[test]
+    fn foundry_profiles() {
+        let project = tempfile::tempdir().unwrap();
+        let path = project.path().join("foundry.toml");
+        std::fs::write(
+            &path,
+            r#"
+[profile.default]
+solc = "0.8.28"
+
+[profile.default.resolc]
+resolc_compile = true
+resolc = "0.4.0"
+
+[profile.ci]
+solc_version = "0.8.30"
+
+[profile.local.resolc]
+resolc = "bin/resolc"
+"#,
+        )
+        .unwrap();
+        let nested = project.path().join("src");
+        std::fs::create_dir(&nested).unwrap();
+        assert_eq!(find(&nested), Some(path.clone()));
+
+        let versions = read(&path).unwrap();
+        let version = |profile: &str| {
+            versions
+                .iter()
+                .find(|version| version.profile.as_deref() == Some(profile))
+                .unwrap()
+        };
+        assert_eq!(version("default").resolc.as_deref(), Some("0.4.0"));
+        assert_eq!(version("ci").resolc, None);
+        assert_eq!(
+            version("local").resolc.as_deref().map(Path::new),
+            Some(project.path().join("bin/resolc").as_path())
+        );
+
+        let no_env = |_: &str| None;
+        let default = requested(&nested, &no_env).unwrap().unwrap();
+        assert_eq!(default.resolc.as_deref(), Some("0.4.0"));
+        assert_eq!(default.solc.as_deref(), Some("0.8.28"));
+        let ci = |_: &str| Some("ci".to_owned());
+        let ci = requested(&nested, &ci).unwrap().unwrap();
+        assert_eq!(ci.profile.as_deref(), Some("ci"));
+        assert_eq!(ci.resolc.as_deref(), Some("0.4.0"));
+        assert_eq!(ci.solc.as_deref(), Some("0.8.30"));
+    }
+
+    #[test]
+    fn hardhat_config() {
+        let project = tempfile::tempdir().unwrap();
+        let path = project.path().join("hardhat.config.ts");
+        std::fs::write(
+            &path,
+            r#"
+import "@parity/hardhat-polkadot";
+// resolc: { version: "0.1.0" }
+const config: HardhatUserConfig = {
+  solidity: { version: "0.8.28", settings: { optimizer: { enabled: true } } },
+  resolc: {
+    compilerSource: "binary",
+    settings: { version: "ignored", optimizer: { enabled: true } },
+    "version": '0.3.0',
+  },
+};
+export default config;
+"#,
+        )
+        .unwrap();
+        assert_eq!(
+            read(&path).unwrap(),
+            [ToolVersion {
+                path: path.clone(),
+                profile: None,
+                resolc: Some("0.3.0".to_owned()),
+                solc: Some("0.8.28".to_owned()),
+            }]
+        );
+
+        std::fs::write(
+            &path,
+            "// Ünïcode ✓\nconst r = /é/;\nconst größe = 1;\nmodule.exports = { solidity: \"0.8.30\", resolc: { version: \"0.4.0\" } };",
+        )
+        .unwrap();
+        let version = &read(&path).unwrap()[0];
+        assert_eq!(version.resolc.as_deref(), Some("0.4.0"));
+        assert_eq!(version.solc.as_deref(), Some("0.8.30"));
+
+        std::fs::write(&path, "module.exports = { solidity: \"0.8.30\" };").unwrap();
+        let version = &read(&path).unwrap()[0];
+        assert_eq!(version.resolc, None);
+        assert_eq!(version.solc.as_deref(), Some("0.8.30"));
+    }
+}
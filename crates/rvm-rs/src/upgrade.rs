@@ -0,0 +1,112 @@
+use std::path::PathBuf;
+
+use semver::Version;
+use serde::Serialize;
+
+use crate::{releases::Binary, spec::Channel};
+
+/// Installed or requested version with the newer releases of its channel, see [`crate::VersionManager::outdated`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct OutdatedVersion {
+    /// Installed or requested version
+    pub version: Version,
+    /// Whether the version is the default one
+    pub is_default: bool,
+    /// Project file pinning the version, if any
+    pub project: Option<PathBuf>,
+    /// Whether the version is installed
+    pub installed: bool,
+    /// Newest newer release of the same channel and compatible range, `None` when up to date
+    pub compatible: Option<Version>,
+    /// Newest newer release of the same channel, `None` when up to date
+    pub latest: Option<Version>,
+}
+
+impl OutdatedVersion {
+    /// Whether a newer release of the channel exists
+    pub fn is_outdated(&self) -> bool {
+        self.latest.is_some()
+    }
+}
+
+/// Where [`crate::VersionManager::upgrade`] records the new version.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UpgradeTarget {
+    /// Global default set with `rvm use`
+    Default,
+    /// Closest `.resolc-version` project file of the directory
+    Project,
+}
+
+/// Result of [`crate::VersionManager::upgrade`].
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct Upgrade {
+    /// Version used before the upgrade, if any
+    pub from: Option<Version>,
+    /// Installed newest version
+    pub to: Binary,
+    /// Project file that was rewritten, `None` when the default was switched
+    pub project: Option<PathBuf>,
+    /// Whether the previous version was removed
+    pub pruned: bool,
+}
+
+/// Whether upgrading from `from` to `to` keeps compatibility: the same major version, or the same minor version
+/// before `1.0.0`
+pub(crate) fn is_compatible(from: &Version, to: &Version) -> bool {
+    from.major == to.major && (from.major != 0 || from.minor == to.minor)
+}
+
+/// Newest version of the channel newer than `version`
+///
+/// # Arguments
+///
+/// * `compatible` - only consider versions compatible with `version`
+pub(crate) fn newer<'a>(
+    version: &Version,
+    releases: impl IntoIterator<Item = &'a Version>,
+    compatible: bool,
+) -> Option<&'a Version> {
+    let channel = Channel::of(version);
+    releases
+        .into_iter()
+        .filter(|release| Channel::of(release) == channel && *release > version)
+        .filter(|release| !compatible || is_compatible(version, release))
+        .max()
+}
+
+#[cfg(test)]
+mod test {
+    use semver::Version;
+
+    use super::newer;
+
+    #[test]
+    fn newer_releases_of_the_channel() {
+        let releases: Vec<Version> = [
+            "0.3.0",
+            "0.3.1",
+            "0.4.0",
+            "0.3.0-nightly.2025.8.9",
+            "0.4.0-nightly.2025.9.1",
+        ]
+        .iter()
+        .map(|version| Version::parse(version).unwrap())
+        .collect();
+        let newer = |version: &str, compatible: bool| {
+            newer(&Version::parse(version).unwrap(), &releases, compatible).map(ToString::to_string)
+        };
+
+        assert_eq!(newer("0.3.0", false).as_deref(), Some("0.4.0"));
+        assert_eq!(newer("0.3.0", true).as_deref(), Some("0.3.1"));
+        assert_eq!(newer("0.4.0", false), None);
+        assert_eq!(
+            newer("0.3.0-nightly.2025.8.1", true).as_deref(),
+            Some("0.3.0-nightly.2025.8.9")
+        );
+        assert_eq!(
+            newer("0.3.0-nightly.2025.8.1", false).as_deref(),
+            Some("0.4.0-nightly.2025.9.1")
+        );
+    }
+}
@@ -0,0 +1,137 @@
+use std::{
+    fs,
+    path::Path,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+use url::Url;
+
+use crate::{fs::BUILD_FILE_NAME, releases::Build};
+
+/// File next to `build.json` describing the installation.
+pub(crate) const INSTALL_FILE_NAME: &str = "install.json";
+
+/// File next to `build.json` whose modification time is the last time the version was run.
+pub(crate) const LAST_USED_FILE_NAME: &str = ".last_used";
+
+/// Uses closer than this to the recorded one aren't written, so running the compiler in a loop doesn't touch the disk.
+const LAST_USED_PRECISION: Duration = Duration::from_secs(60);
+
+/// Metadata of an installation, stored in `install.json`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct InstallMetadata {
+    /// Seconds since the Unix epoch
+    installed_at: u64,
+    /// URL the binary was installed from, or the path of a linked binary
+    pub(crate) source: Url,
+    /// Registry the binary was installed from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) registry: Option<String>,
+}
+
+impl InstallMetadata {
+    /// Metadata of the build installed now
+    pub(crate) fn new(build: &Build) -> Self {
+        Self {
+            installed_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+            source: build.url.clone(),
+            registry: build.registry.clone(),
+        }
+    }
+
+    /// Reads the metadata of the installation in `dir`
+    pub(crate) fn read(dir: &Path) -> Option<Self> {
+        let metadata = fs::read_to_string(dir.join(INSTALL_FILE_NAME)).ok()?;
+        serde_json::from_str(&metadata).ok()
+    }
+
+    pub(crate) fn write(&self, dir: &Path) -> std::io::Result<()> {
+        fs::write(dir.join(INSTALL_FILE_NAME), serde_json::to_vec(self)?)
+    }
+
+    pub(crate) fn installed_at(&self) -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(self.installed_at)
+    }
+}
+
+/// Records that the binary is being run, if it's installed in the store.
+///
+/// The time is kept as the modification time of a marker file, so concurrent runs don't need a lock:
+/// whichever write lands last wins, and they all carry the same time give or take.
+pub(crate) fn record_use(binary: &Path) {
+    let Some(dir) = binary.parent() else {
+        return;
+    };
+    // Linked binaries live outside of the store.
+    if !dir.join(BUILD_FILE_NAME).is_file() {
+        return;
+    }
+    let now = SystemTime::now();
+    if last_used(dir).is_some_and(|last_used| {
+        now.duration_since(last_used).unwrap_or_default() < LAST_USED_PRECISION
+    }) {
+        return;
+    }
+    let _ = fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(dir.join(LAST_USED_FILE_NAME))
+        .and_then(|marker| marker.set_modified(now));
+}
+
+/// Last time the installation in `dir` was run
+pub(crate) fn last_used(dir: &Path) -> Option<SystemTime> {
+    fs::metadata(dir.join(LAST_USED_FILE_NAME))
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::{Duration, SystemTime};
+
+    use super::{last_used, record_use, InstallMetadata};
+    use crate::fs::BUILD_FILE_NAME;
+
+    #[test]
+    fn use_is_recorded_for_installed_binaries() {
+        let store = tempfile::tempdir().unwrap();
+        let dir = store.path().join("0.4.0");
+        std::fs::create_dir(&dir).unwrap();
+        let binary = dir.join("resolc");
+        std::fs::write(&binary, "binary").unwrap();
+
+        record_use(&binary);
+        assert_eq!(last_used(&dir), None);
+
+        std::fs::write(dir.join(BUILD_FILE_NAME), "{}").unwrap();
+        record_use(&binary);
+        let recorded = last_used(&dir).unwrap();
+        assert!(SystemTime::now().duration_since(recorded).unwrap() < Duration::from_secs(60));
+    }
+
+    #[test]
+    fn install_metadata_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let metadata: InstallMetadata = serde_json::from_value(serde_json::json!({
+            "installedAt": 1_750_000_000,
+            "source": "https://example.com/resolc",
+            "registry": "official"
+        }))
+        .unwrap();
+        metadata.write(dir.path()).unwrap();
+
+        let read = InstallMetadata::read(dir.path()).unwrap();
+        assert_eq!(read, metadata);
+        assert_eq!(
+            read.installed_at(),
+            SystemTime::UNIX_EPOCH + Duration::from_secs(1_750_000_000)
+        );
+    }
+}